@@ -8,7 +8,7 @@ use jano::{wgpu, FrameStats, TouchTranslater, Window};
 use mlsim_common::app::{App, AppInput};
 use mlsim_common::egui;
 use mlsim_common::glam::{uvec2, vec2};
-use mlsim_common::{save::Project, settings::Settings, Platform};
+use mlsim_common::{save, save::Project, settings::Settings, Platform};
 
 use std::path::PathBuf;
 use std::sync::{
@@ -20,22 +20,24 @@ fn save_dir() -> PathBuf {
     jano::android().external_data_path().unwrap()
 }
 
-fn save_data<T: serde::Serialize>(
-    filename: &str,
-    data: &T,
-) -> Result<PathBuf, (PathBuf, std::io::Error)> {
+fn save_bytes(filename: &str, bytes: &[u8]) -> Result<PathBuf, (PathBuf, std::io::Error)> {
     let dir = save_dir();
     _ = std::fs::create_dir(&dir);
-    let bytes = bincode::serialize(data).unwrap();
     let path = dir.join(filename);
-    std::fs::write(&path, &bytes)
+    std::fs::write(&path, bytes)
         .map(|()| path.clone())
         .map_err(|err| (path, err))
 }
 
+fn save_data<T: serde::Serialize>(
+    filename: &str,
+    data: &T,
+) -> Result<PathBuf, (PathBuf, std::io::Error)> {
+    save_bytes(filename, &bincode::serialize(data).unwrap())
+}
+
 fn load_data<T: for<'a> serde::Deserialize<'a>>(filename: &str) -> std::io::Result<T> {
-    let dir = save_dir();
-    let bytes = std::fs::read(dir.join(filename))?;
+    let bytes = std::fs::read(save_dir().join(filename))?;
     bincode::deserialize(&bytes).map_err(|_| {
         std::io::Error::new(
             std::io::ErrorKind::Other,
@@ -92,6 +94,15 @@ impl Platform for AndroidPlatform {
         rs.map(|_| ()).map_err(|(_path, err)| err)
     }
 
+    fn read_file(path: &str) -> std::io::Result<Vec<u8>> {
+        log::info!("Reading {path}...");
+        std::fs::read(path)
+    }
+    fn write_file(path: &str, data: &[u8]) -> std::io::Result<()> {
+        log::info!("Writing {path}...");
+        std::fs::write(path, data)
+    }
+
     #[rustfmt::skip]
     fn can_open_dirs() -> bool { false }
 
@@ -121,10 +132,17 @@ impl Platform for AndroidPlatform {
 
     fn load_project(name: &str) -> std::io::Result<Project> {
         log::info!("Reading {name}.project...");
-        load_data(&format!("{name}.project"))
+        let filename = format!("{name}.project");
+        let bytes = std::fs::read(save_dir().join(&filename))?;
+        save::decode_project(&bytes).map_err(|err| {
+            std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("Failed to parse data from file {filename:?}: {err}"),
+            )
+        })
     }
     fn save_project(name: &str, project: Project) -> std::io::Result<()> {
-        let rs = save_data(&format!("{name}.project"), &project);
+        let rs = save_bytes(&format!("{name}.project"), &save::encode_project(&project));
         match &rs {
             Ok(path) => log::info!("Saved project {name:?} to {path:?}"),
             Err((path, err)) => log::warn!("Failed to save project {name:?} to {path:?} : {err:?}"),
@@ -0,0 +1,232 @@
+//! Runs the chips and scenes of a project without a window, for scripts and CI.
+
+mod stimuli;
+
+use mlsim_common::save::{self, Project};
+use mlsim_common::sim::bench::TestBench;
+use mlsim_common::sim::vector;
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+usage:
+    mlsim-cli list <project>
+    mlsim-cli test <project> [chip...]
+    mlsim-cli run <project> <chip or scene> <ticks> [--stimuli <file>] [--trace]
+
+<project> is the path of a .project file, or the name of a project saved by the app.";
+
+/// Finds a project by it's path, or by it's name in the directory the app saves projects in.
+fn project_path(arg: &str) -> PathBuf {
+    let path = PathBuf::from(arg);
+    if path.exists() || path.extension().is_some() {
+        return path;
+    }
+    match directories::ProjectDirs::from("com", "", "mlsim") {
+        Some(dirs) => dirs.data_dir().join(format!("{arg}.project")),
+        None => path,
+    }
+}
+
+fn load_project(arg: &str) -> Result<Project, String> {
+    let path = project_path(arg);
+    let bytes = std::fs::read(&path).map_err(|err| format!("can't read {path:?}: {err}"))?;
+    let mut project = save::decode_project(&bytes)
+        .map_err(|err| format!("failed to parse a project from {path:?}: {err}"))?;
+    project.on_load();
+    Ok(project)
+}
+
+fn names<T>(pins: &[(String, T)]) -> Vec<String> {
+    pins.iter().map(|(name, _)| name.clone()).collect()
+}
+
+fn list(project: &Project) {
+    println!("scenes:");
+    for scene in &project.scenes {
+        let pins = |states: &[(_, String)]| {
+            (states.iter())
+                .map(|(_, name)| name.as_str())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        println!(
+            "    {}  in: {}  out: {}",
+            scene.save_attrs.name,
+            pins(&scene.l_nodes.states),
+            pins(&scene.r_nodes.states)
+        );
+    }
+    println!("chips:");
+    for chip in &project.library.chips {
+        let pins = |nodes: &[(String, _, _)]| {
+            (nodes.iter())
+                .map(|(name, ..)| name.as_str())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        println!(
+            "    {} [{}]  in: {}  out: {}  tests: {}",
+            chip.attrs.name,
+            chip.attrs.category,
+            pins(&chip.l_nodes),
+            pins(&chip.r_nodes),
+            chip.tests.len()
+        );
+    }
+}
+
+/// Runs the test vectors of the named chips, or of every chip that has any.
+/// Returns if all of them passed.
+fn test(project: &Project, chips: &[String]) -> Result<bool, String> {
+    for name in chips {
+        if !project
+            .library
+            .chips
+            .iter()
+            .any(|chip| chip.attrs.name == *name)
+        {
+            return Err(format!("there's no chip named {name:?}"));
+        }
+    }
+    let selected = (project.library.chips.iter())
+        .filter(|chip| chips.is_empty() || chips.contains(&chip.attrs.name));
+    let (mut passed, mut failed) = (0, 0);
+    for chip in selected {
+        if chip.tests.is_empty() {
+            if !chips.is_empty() {
+                println!("SKIP {}: no tests", chip.attrs.name);
+            }
+            continue;
+        }
+        match vector::run(chip, &project.library.tables, &chip.tests) {
+            Ok(count) => {
+                println!("PASS {} ({count} vectors)", chip.attrs.name);
+                passed += 1;
+            }
+            Err(failure) => {
+                let message = failure.message(&chip.tests[failure.row]);
+                println!("FAIL {}: {message}", chip.attrs.name);
+                failed += 1;
+            }
+        }
+    }
+    println!("{passed} passed, {failed} failed");
+    Ok(failed == 0)
+}
+
+/// Runs a chip or scene for a number of ticks, returning if every expected output matched.
+fn run(
+    project: &mut Project,
+    target: &str,
+    ticks: u64,
+    stimuli: Option<&Path>,
+    trace: bool,
+) -> Result<bool, String> {
+    let scene = project
+        .scenes
+        .iter_mut()
+        .find(|scene| scene.save_attrs.name == target);
+    let mut bench = match scene {
+        Some(scene) => {
+            scene.connect_nets();
+            scene.connect_pulls();
+            TestBench::from_scene(scene)
+        }
+        None => {
+            let chip = (project.library.chips.iter())
+                .find(|chip| chip.attrs.name == target)
+                .ok_or_else(|| format!("there's no scene or chip named {target:?}"))?;
+            TestBench::from_chip(chip)
+        }
+    };
+    let tables = &project.library.tables;
+    let (inputs, outputs) = (names(&bench.inputs), names(&bench.outputs));
+    let widths = bench.input_widths(tables);
+    let stimuli = match stimuli {
+        Some(path) => {
+            let text = std::fs::read_to_string(path)
+                .map_err(|err| format!("can't read {path:?}: {err}"))?;
+            stimuli::parse(&text, &inputs, &outputs, &widths)
+                .map_err(|err| format!("{path:?} {err}"))?
+        }
+        None => vec![],
+    };
+
+    let values = |bench: &TestBench| {
+        (outputs.iter().enumerate())
+            .map(|(idx, name)| format!("{name}={}", bench.output(idx)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let mut ok = true;
+    let mut next = stimuli.iter().peekable();
+    for tick in 0..=ticks {
+        while let Some(stimulus) = next.next_if(|stimulus| stimulus.tick <= tick) {
+            for (idx, expected) in &stimulus.expect {
+                let actual = bench.output(*idx);
+                if actual != *expected {
+                    println!(
+                        "tick {tick}: expected {}={expected}, got {actual}",
+                        outputs[*idx]
+                    );
+                    ok = false;
+                }
+            }
+            for (idx, value) in &stimulus.inputs {
+                bench.set_input(*idx, *value);
+            }
+        }
+        if tick == ticks {
+            break;
+        }
+        bench.sim.update(tables);
+        if trace {
+            println!("{}: {}", tick + 1, values(&bench));
+        }
+    }
+    println!("{}", values(&bench));
+    Ok(ok)
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match &args[..] {
+        [cmd, project] if cmd == "list" => load_project(project).map(|project| {
+            list(&project);
+            true
+        }),
+        [cmd, project, chips @ ..] if cmd == "test" => {
+            load_project(project).and_then(|project| test(&project, chips))
+        }
+        [cmd, project, target, ticks, options @ ..] if cmd == "run" => (|| {
+            let ticks =
+                (ticks.parse()).map_err(|_| format!("{ticks:?} isn't a number of ticks"))?;
+            let mut stimuli = None;
+            let mut trace = false;
+            let mut options = options.iter();
+            while let Some(option) = options.next() {
+                match option.as_str() {
+                    "--stimuli" => {
+                        let path = options.next().ok_or("--stimuli needs a file")?;
+                        stimuli = Some(PathBuf::from(path));
+                    }
+                    "--trace" => trace = true,
+                    _ => return Err(format!("unknown option {option:?}")),
+                }
+            }
+            let mut project = load_project(project)?;
+            run(&mut project, target, ticks, stimuli.as_deref(), trace)
+        })(),
+        _ => Err(String::from("invalid arguments")),
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("error: {err}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
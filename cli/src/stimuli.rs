@@ -0,0 +1,106 @@
+//! Input values to set, and output values to expect, at given ticks of a run.
+//!
+//! Each line is a tick followed by `pin=value` pairs, e.g. `10 a=1 b=0x3`. If the pairs are
+//! preceded by `expect`, they're the values the outputs should have at that tick instead.
+//! Lines starting with `#` are comments.
+
+use mlsim_common::sim::vector::{check_width, parse_value};
+
+pub struct Stimulus {
+    pub tick: u64,
+    /// The inputs to set, by index.
+    pub inputs: Vec<(usize, u8)>,
+    /// The expected outputs, by index.
+    pub expect: Vec<(usize, u8)>,
+}
+
+/// Parses the stimuli for the given input and output names, and the number of bits each input is
+/// wide.
+pub fn parse(
+    text: &str,
+    inputs: &[String],
+    outputs: &[String],
+    widths: &[u8],
+) -> Result<Vec<Stimulus>, String> {
+    let mut stimuli = vec![];
+    let lines = (text.lines().enumerate())
+        .map(|(idx, line)| (idx + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+    for (line_idx, line) in lines {
+        let err = |msg: String| format!("line {line_idx}: {msg}");
+        let mut words = line.split_whitespace().peekable();
+        let tick = words.next().unwrap_or_default();
+        let tick = (tick.parse()).map_err(|_| err(format!("{tick:?} isn't a tick")))?;
+        let expect = words.next_if_eq(&"expect").is_some();
+        let pins = match expect {
+            true => outputs,
+            false => inputs,
+        };
+
+        let mut values = vec![];
+        for word in words {
+            let Some((name, value)) = word.split_once('=') else {
+                return Err(err(format!("expected `pin=value`, got {word:?}")));
+            };
+            let pin = (pins.iter().position(|pin| pin == name))
+                .ok_or_else(|| err(format!("there's no pin named {name:?}")))?;
+            let value = parse_value(value)
+                .map_err(err)?
+                .ok_or_else(|| err(format!("{name} needs a value")))?;
+            if !expect {
+                check_width(name, value, widths[pin]).map_err(err)?;
+            }
+            values.push((pin, value));
+        }
+        let (inputs, expect) = match expect {
+            true => (vec![], values),
+            false => (values, vec![]),
+        };
+        stimuli.push(Stimulus {
+            tick,
+            inputs,
+            expect,
+        });
+    }
+    stimuli.sort_by_key(|stimulus| stimulus.tick);
+    Ok(stimuli)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<Vec<Stimulus>, String> {
+        let inputs = ["a".to_string(), "bus".to_string()];
+        super::parse(text, &inputs, &["q".to_string()], &[1, 4])
+    }
+
+    #[test]
+    fn sorted_by_tick() {
+        let stimuli = parse("# comment\n10 expect q=1\n0 a=1 bus=0xf\n\n5 a=0").unwrap();
+        let ticks: Vec<u64> = stimuli.iter().map(|stimulus| stimulus.tick).collect();
+        assert_eq!(ticks, [0, 5, 10]);
+        assert_eq!(stimuli[0].inputs, [(0, 1), (1, 15)]);
+        assert!(stimuli[0].expect.is_empty());
+        assert_eq!(stimuli[2].expect, [(0, 1)]);
+        assert!(stimuli[2].inputs.is_empty());
+    }
+
+    #[test]
+    fn errors() {
+        let err = |text: &str| parse(text).err().unwrap();
+        assert_eq!(err("t a=1"), "line 1: \"t\" isn't a tick");
+        assert_eq!(err("0 a"), "line 1: expected `pin=value`, got \"a\"");
+        assert_eq!(err("0 q=1"), "line 1: there's no pin named \"q\"");
+        assert_eq!(err("0 expect a=1"), "line 1: there's no pin named \"a\"");
+        assert_eq!(err("0 a=x"), "line 1: a needs a value");
+        assert_eq!(
+            err("0\n1 a=2"),
+            "line 2: 2 doesn't fit in a, which is 1 bits wide"
+        );
+        assert_eq!(
+            err("0 bus=16"),
+            "line 1: 16 doesn't fit in bus, which is 4 bits wide"
+        );
+    }
+}
@@ -1,14 +1,13 @@
 use crate::gpu::Gpu;
 use crate::settings::Settings;
-use crate::sim::save::{ChipSave, IoType};
-use crate::sim::scene::{Chip as SceneChip, Rotation, Scene};
-use crate::sim::{Node, SourceTy};
+use crate::sim::save::ChipSave;
+use crate::sim::scene::Scene;
 use crate::ui::pages::{HomePage, Page, PageOutput};
 use crate::Platform;
 
 use egui::PlatformOutput;
 use egui_wgpu::Renderer;
-use glam::{UVec2, Vec2};
+use glam::UVec2;
 
 #[derive(Clone, Debug)]
 pub struct AppInput {
@@ -218,53 +217,7 @@ pub fn create_chip_save(scene: &Scene) -> ChipSave {
         l_nodes,
         r_nodes,
         inner_nodes,
+        natives: scene.sim.natives.clone(),
+        tests: vec![],
     }
 }
-
-pub fn place_chip(
-    scene: &mut Scene,
-    save_id: Option<usize>,
-    save: &ChipSave,
-    pos: Vec2,
-    rotation: Rotation,
-) {
-    let mut l_nodes = vec![];
-    let mut r_nodes = vec![];
-    let mut inner_nodes = vec![];
-    let region = scene.sim.alloc_region(save.region_size);
-
-    fn io_ty(node: &Node) -> IoType {
-        match node.source().ty() {
-            SourceTy::NONE => IoType::Input,
-            _ => IoType::Output,
-        }
-    }
-
-    for (name, addr, state) in &save.l_nodes {
-        let addr = region.map(*addr);
-        scene.sim.set_node(addr, region.map_node(*state));
-        l_nodes.push((addr, name.clone(), io_ty(state)));
-    }
-    for (name, addr, state) in &save.r_nodes {
-        let addr = region.map(*addr);
-        scene.sim.set_node(addr, region.map_node(*state));
-        r_nodes.push((addr, name.clone(), io_ty(state)));
-    }
-    for (addr, state) in &save.inner_nodes {
-        let addr = region.map(*addr);
-        scene.sim.set_node(addr, region.map_node(*state));
-        inner_nodes.push(addr);
-    }
-
-    let chip = SceneChip {
-        attrs: save.attrs.clone(),
-        region,
-        pos,
-        rotation,
-        save: save_id,
-        l_nodes,
-        r_nodes,
-        inner_nodes,
-    };
-    scene.add_device(chip);
-}
@@ -1,15 +1,22 @@
+#[cfg(feature = "ui")]
 pub mod app;
+pub mod geom;
+#[cfg(feature = "ui")]
 pub mod gpu;
 pub mod settings;
 pub mod sim;
+#[cfg(feature = "ui")]
 pub mod ui;
 
+#[cfg(feature = "ui")]
 pub use app::App;
 pub use sim::save;
 
+#[cfg(feature = "ui")]
 pub use egui;
 pub use glam;
 pub use log;
+#[cfg(feature = "ui")]
 pub use wgpu;
 
 use crate::save::Project;
@@ -39,6 +46,11 @@ pub trait Platform {
     fn delete_project(name: &str) -> std::io::Result<()>;
     fn rename_project(name: &str, new_name: &str) -> std::io::Result<()>;
 
+    /// Reads a file the user picked by path, such as memory contents to import.
+    fn read_file(path: &str) -> std::io::Result<Vec<u8>>;
+    /// Writes a file the user picked by path, such as an exported waveform.
+    fn write_file(path: &str, data: &[u8]) -> std::io::Result<()>;
+
     fn can_open_dirs() -> bool;
     fn open_save_dir() -> std::io::Result<()>;
 
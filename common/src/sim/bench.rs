@@ -0,0 +1,255 @@
+//! Running a scene or chip on it's own, to see what it outputs for each combination of inputs.
+
+use crate::sim::save::ChipSave;
+use crate::sim::scene::Scene;
+use crate::sim::snapshot::Snapshot;
+use crate::sim::{NodeAddr, Sim, SourceTy, TruthTable};
+
+/// The most inputs a table is evaluated for, as every combination is simulated.
+pub const MAX_INPUTS: usize = 12;
+/// The most outputs that fit in a row of a table.
+pub const MAX_OUTPUTS: usize = 64;
+
+/// A simulation of a single scene or chip, driven through it's external pins.
+pub struct TestBench {
+    pub sim: Sim,
+    pub inputs: Vec<(String, NodeAddr)>,
+    pub outputs: Vec<(String, NodeAddr)>,
+}
+impl TestBench {
+    /// Copies the simulation of a scene, with it's current state.
+    pub fn from_scene(scene: &Scene) -> Self {
+        let mut sim = scene.sim.clone();
+        sim.recorder = Default::default();
+        let pins = |states: &[(NodeAddr, String)]| {
+            (states.iter())
+                .map(|(addr, name)| (name.clone(), *addr))
+                .collect()
+        };
+        Self {
+            sim,
+            inputs: pins(&scene.l_nodes.states),
+            outputs: pins(&scene.r_nodes.states),
+        }
+    }
+
+    /// Places a chip in a new simulation.
+    pub fn from_chip(save: &ChipSave) -> Self {
+        let mut sim = Sim::default();
+        let region = sim.alloc_region(save.region_size);
+        let mut pins = |nodes: &[(String, NodeAddr, crate::sim::Node)]| {
+            (nodes.iter())
+                .map(|(name, addr, node)| {
+                    let addr = region.map(*addr);
+                    sim.set_node(addr, region.map_node(*node));
+                    (name.clone(), addr)
+                })
+                .collect()
+        };
+        let inputs = pins(&save.l_nodes);
+        let outputs = pins(&save.r_nodes);
+        for (addr, node) in &save.inner_nodes {
+            sim.set_node(region.map(*addr), region.map_node(*node));
+        }
+        for native in &save.natives {
+            sim.natives.push(native.map(&region));
+        }
+        Self {
+            sim,
+            inputs,
+            outputs,
+        }
+    }
+
+    /// Sets input `n` to bit `n` of `bits`.
+    pub fn set_inputs(&mut self, bits: u64) {
+        for (idx, (_, addr)) in self.inputs.iter().enumerate() {
+            self.sim.mut_node(*addr).set_state((bits >> idx) as u8 & 1);
+        }
+    }
+
+    pub fn set_input(&mut self, idx: usize, state: u8) {
+        self.sim.mut_node(self.inputs[idx].1).set_state(state);
+    }
+
+    /// The number of bits of each input that the simulation reads, which it's values have to fit
+    /// in. Wires copy every bit, so the bits read through them count too, and an input that nothing
+    /// reads can hold any value.
+    pub fn input_widths(&self, tables: &[TruthTable]) -> Vec<u8> {
+        // The nodes that read each node, and the bits they read. A reader copying every bit reads
+        // as many bits as it's own readers, shifted by the given number of bits.
+        let mut readers: Vec<Vec<(Option<usize>, u8)>> = vec![vec![]; self.sim.nodes.len()];
+        let mut read = |addr: u32, reader: Option<usize>, bits: u8| {
+            if let Some(list) = readers.get_mut(addr as usize) {
+                list.push((reader, bits));
+            }
+        };
+        for (idx, node) in self.sim.nodes.iter().enumerate() {
+            let src = node.source();
+            match src.ty() {
+                SourceTy::COPY => {
+                    let copy = src.as_copy();
+                    let addr = copy.addr().0;
+                    match (copy.count(), copy.width()) {
+                        (0, 0) => read(addr, Some(idx), copy.shift()),
+                        (0, width) => read(addr, None, copy.shift() + width),
+                        (count, _) => (addr..addr + count as u32).for_each(|a| read(a, None, 1)),
+                    }
+                }
+                SourceTy::TABLE => {
+                    let table = src.as_table();
+                    let count = tables
+                        .get(table.id().0 as usize)
+                        .map_or(0, |t| t.num_inputs);
+                    let addr = table.inputs().0;
+                    (addr..addr + count as u32).for_each(|a| read(a, None, 1));
+                }
+                _ => {}
+            }
+        }
+
+        fn width(readers: &[Vec<(Option<usize>, u8)>], addr: usize, memo: &mut [Option<u8>]) -> u8 {
+            if let Some(bits) = memo[addr] {
+                return bits;
+            }
+            if readers[addr].is_empty() {
+                return 8;
+            }
+            // A loop of wires doesn't read any more bits
+            memo[addr] = Some(0);
+            let bits = (readers[addr].iter())
+                .map(|(reader, bits)| match reader {
+                    Some(reader) => bits.saturating_add(width(readers, *reader, memo)),
+                    None => *bits,
+                })
+                .max()
+                .unwrap_or(8)
+                .min(8);
+            memo[addr] = Some(bits);
+            bits
+        }
+        let mut memo = vec![None; readers.len()];
+        (self.inputs.iter())
+            .map(|(_, addr)| width(&readers, addr.0 as usize, &mut memo))
+            .collect()
+    }
+
+    pub fn output(&self, idx: usize) -> u8 {
+        self.sim.get_node(self.outputs[idx].1).state()
+    }
+
+    /// The outputs, with output `n` as bit `n`.
+    pub fn outputs(&self) -> u64 {
+        (self.outputs.iter().enumerate())
+            .map(|(idx, (_, addr))| ((self.sim.get_node(*addr).state() != 0) as u64) << idx)
+            .fold(0, |acc, bit| acc | bit)
+    }
+
+    /// Updates the simulation until no node changes, returning the number of ticks that took,
+    /// or None if it didn't settle within `max_ticks`.
+    pub fn settle(&mut self, tables: &[TruthTable], max_ticks: u32) -> Option<u32> {
+        for tick in 0..max_ticks {
+            let prev = self.sim.nodes.clone();
+            self.sim.update(tables);
+            let stable = (prev.iter().zip(&self.sim.nodes)).all(|(a, b)| a.state() == b.state());
+            if stable {
+                return Some(tick);
+            }
+        }
+        None
+    }
+
+    /// The outputs that change at least once in the next `ticks` ticks.
+    pub fn unsettled(&mut self, tables: &[TruthTable], ticks: u32) -> u64 {
+        let first = self.outputs();
+        let mut changed = 0;
+        for _ in 0..ticks {
+            self.sim.update(tables);
+            changed |= self.outputs() ^ first;
+        }
+        changed
+    }
+}
+
+/// The outputs of a scene or chip for every combination of it's inputs.
+#[derive(Clone)]
+pub struct LiveTable {
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
+    /// The outputs for each combination of inputs, indexed like [TruthTable::map].
+    pub map: Vec<u64>,
+    /// The outputs that never settled for each combination of inputs.
+    pub unsettled: Vec<u64>,
+}
+impl LiveTable {
+    /// Simulates every combination of inputs, starting from the current state of the bench each time.
+    pub fn evaluate(
+        bench: &mut TestBench,
+        tables: &[TruthTable],
+        max_ticks: u32,
+    ) -> Result<Self, String> {
+        if bench.inputs.len() > MAX_INPUTS {
+            return Err(format!(
+                "{} inputs is too many, at most {MAX_INPUTS} are supported",
+                bench.inputs.len()
+            ));
+        }
+        if bench.outputs.len() > MAX_OUTPUTS {
+            return Err(format!(
+                "{} outputs is too many, at most {MAX_OUTPUTS} are supported",
+                bench.outputs.len()
+            ));
+        }
+
+        let start = Snapshot::new(&bench.sim);
+        let rows = 1usize << bench.inputs.len();
+        let mut map = Vec::with_capacity(rows);
+        let mut unsettled = Vec::with_capacity(rows);
+        for row in 0..rows {
+            start.restore(&mut bench.sim);
+            bench.set_inputs(row as u64);
+            let settled = bench.settle(tables, max_ticks).is_some();
+            map.push(bench.outputs());
+            unsettled.push(match settled {
+                true => 0,
+                // Watch the outputs a while longer, to tell which of them keep changing
+                false => bench.unsettled(tables, max_ticks.clamp(1, 64)),
+            });
+        }
+        start.restore(&mut bench.sim);
+
+        let names =
+            |pins: &[(String, NodeAddr)]| pins.iter().map(|(name, _)| name.clone()).collect();
+        Ok(Self {
+            inputs: names(&bench.inputs),
+            outputs: names(&bench.outputs),
+            map,
+            unsettled,
+        })
+    }
+
+    pub fn output_mask(&self) -> u64 {
+        u64::MAX >> (64 - self.outputs.len().max(1))
+    }
+
+    /// If `table` has the same number of inputs and outputs, so it can be compared.
+    pub fn matches_shape(&self, table: &TruthTable) -> bool {
+        table.num_inputs as usize == self.inputs.len()
+            && table.num_outputs as usize == self.outputs.len()
+    }
+
+    /// The outputs of each row that differ from `table`, counting unsettled outputs as different.
+    /// Outputs the table doesn't care about never differ.
+    pub fn diff(&self, table: &TruthTable) -> Vec<u64> {
+        let mask = self.output_mask();
+        (self.map.iter().zip(&self.unsettled).enumerate())
+            .map(|(row, (outputs, unsettled))| match table.map.get(row) {
+                Some(expected) => {
+                    let dont_care = table.dont_care.get(row).copied().unwrap_or(0);
+                    ((outputs ^ expected) | unsettled) & !dont_care & mask
+                }
+                None => mask,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,49 @@
+//! Conditions on node states that pause the simulation.
+
+use crate::sim::scene::NodeIdent;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Condition {
+    Rises,
+    Falls,
+    /// The state of the node becomes this value.
+    Equals(u8),
+}
+impl Condition {
+    /// If the condition is met by the state of a node changing from `prev` to `state`.
+    /// Conditions only trigger when the state changes, so resuming doesn't pause again immediately.
+    pub fn triggered(self, prev: u8, state: u8) -> bool {
+        match self {
+            Self::Rises => prev == 0 && state != 0,
+            Self::Falls => prev != 0 && state == 0,
+            Self::Equals(value) => prev != value && state == value,
+        }
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            Self::Rises => String::from("rises"),
+            Self::Falls => String::from("falls"),
+            Self::Equals(value) => format!("== {value:#04X}"),
+        }
+    }
+}
+
+/// Pauses the simulation when a node meets it's condition.
+/// Wires are watched through the node that drives them.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Breakpoint {
+    pub node: NodeIdent,
+    pub condition: Condition,
+    pub enabled: bool,
+}
+impl Breakpoint {
+    pub fn new(node: NodeIdent) -> Self {
+        Self {
+            node,
+            condition: Condition::Rises,
+            enabled: true,
+        }
+    }
+}
@@ -0,0 +1,509 @@
+//! Boolean expressions, and compiling them into chips.
+//!
+//! A program is a list of `output = expression` lines, that can also be separated by `;`.
+//! From the highest precedence to the lowest, the operators are `!` (or `~`), `&` (or `*`),
+//! `^` and `|` (or `+`). Names that aren't outputs defined on an earlier line are inputs,
+//! in the order they first appear.
+
+use crate::sim::bench::{MAX_INPUTS, MAX_OUTPUTS};
+use crate::sim::save::{create_chip_from_scene, ChipAttrs, Library};
+use crate::sim::scene::{place_chip, BuiltinDevice, BuiltinDeviceTy, NodeIdent, Scene, Wire, UNIT};
+use crate::sim::Source;
+
+use glam::{vec2, Vec2};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Const(bool),
+    /// The index of an input.
+    Input(usize),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Xor(Box<Expr>, Box<Expr>),
+}
+impl Expr {
+    /// The value of the expression, with input `n` as bit `n` of `inputs`.
+    pub fn eval(&self, inputs: u64) -> bool {
+        match self {
+            Self::Const(value) => *value,
+            Self::Input(idx) => (inputs >> idx) & 1 != 0,
+            Self::Not(a) => !a.eval(inputs),
+            Self::And(a, b) => a.eval(inputs) && b.eval(inputs),
+            Self::Or(a, b) => a.eval(inputs) || b.eval(inputs),
+            Self::Xor(a, b) => a.eval(inputs) != b.eval(inputs),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Name(String),
+    Const(bool),
+    Not,
+    And,
+    Or,
+    Xor,
+    Open,
+    Close,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '!' | '~' => Token::Not,
+            '&' | '*' => Token::And,
+            '|' | '+' => Token::Or,
+            '^' => Token::Xor,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '0' => Token::Const(false),
+            '1' => Token::Const(true),
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut name = String::from(c);
+                while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_') {
+                    name.push(c);
+                }
+                Token::Name(name)
+            }
+            c => return Err(format!("unexpected {c:?}")),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// The names and expressions of the outputs of a chip.
+#[derive(Clone, Debug, Default)]
+pub struct Program {
+    pub inputs: Vec<String>,
+    pub outputs: Vec<(String, Expr)>,
+}
+impl Program {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut program = Self::default();
+        let lines = (text.lines().enumerate())
+            .flat_map(|(idx, line)| line.split(';').map(move |stmt| (idx + 1, stmt)))
+            .filter(|(_, stmt)| !stmt.trim().is_empty());
+        for (line, stmt) in lines {
+            program
+                .parse_stmt(stmt)
+                .map_err(|err| format!("line {line}: {err}"))?;
+        }
+        if program.outputs.is_empty() {
+            return Err(String::from(
+                "there are no outputs, write one like `out = a & b`",
+            ));
+        }
+        if program.inputs.len() > MAX_INPUTS || program.outputs.len() > MAX_OUTPUTS {
+            return Err(format!(
+                "at most {MAX_INPUTS} inputs and {MAX_OUTPUTS} outputs are supported"
+            ));
+        }
+        Ok(program)
+    }
+
+    fn parse_stmt(&mut self, stmt: &str) -> Result<(), String> {
+        let Some((name, expr)) = stmt.split_once('=') else {
+            return Err(String::from("expected `output = expression`"));
+        };
+        let name = name.trim();
+        if tokenize(name)?[..] != [Token::Name(name.into())] {
+            return Err(format!("{name:?} isn't a valid output name"));
+        }
+        if self.inputs.iter().any(|input| input == name) {
+            return Err(format!("{name} is used as an input before it's defined"));
+        }
+        if self.outputs.iter().any(|(output, _)| output == name) {
+            return Err(format!("{name} is defined more than once"));
+        }
+
+        let tokens = tokenize(expr)?;
+        let mut pos = 0;
+        let expr = self.parse_or(&tokens, &mut pos)?;
+        if let Some(token) = tokens.get(pos) {
+            return Err(format!("unexpected {token:?} after the expression"));
+        }
+        self.outputs.push((name.into(), expr));
+        Ok(())
+    }
+
+    fn parse_or(&mut self, tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
+        let mut expr = self.parse_xor(tokens, pos)?;
+        while tokens.get(*pos) == Some(&Token::Or) {
+            *pos += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_xor(tokens, pos)?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_xor(&mut self, tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
+        let mut expr = self.parse_and(tokens, pos)?;
+        while tokens.get(*pos) == Some(&Token::Xor) {
+            *pos += 1;
+            expr = Expr::Xor(Box::new(expr), Box::new(self.parse_and(tokens, pos)?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self, tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
+        let mut expr = self.parse_unary(tokens, pos)?;
+        while tokens.get(*pos) == Some(&Token::And) {
+            *pos += 1;
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_unary(tokens, pos)?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self, tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
+        let token = tokens.get(*pos).cloned();
+        *pos += 1;
+        match token {
+            Some(Token::Not) => Ok(Expr::Not(Box::new(self.parse_unary(tokens, pos)?))),
+            Some(Token::Const(value)) => Ok(Expr::Const(value)),
+            Some(Token::Open) => {
+                let expr = self.parse_or(tokens, pos)?;
+                if tokens.get(*pos) != Some(&Token::Close) {
+                    return Err(String::from("expected `)`"));
+                }
+                *pos += 1;
+                Ok(expr)
+            }
+            Some(Token::Name(name)) => {
+                // Earlier outputs are substituted, so they can be used like variables
+                if let Some((_, expr)) = self.outputs.iter().find(|(output, _)| *output == name) {
+                    return Ok(expr.clone());
+                }
+                let idx = match self.inputs.iter().position(|input| *input == name) {
+                    Some(idx) => idx,
+                    None => {
+                        self.inputs.push(name);
+                        self.inputs.len() - 1
+                    }
+                };
+                Ok(Expr::Input(idx))
+            }
+            Some(token) => Err(format!("unexpected {token:?}")),
+            None => Err(String::from("the expression ends early")),
+        }
+    }
+
+    /// The outputs for every combination of inputs, indexed like [crate::sim::TruthTable::map].
+    pub fn truth_map(&self) -> Box<[u64]> {
+        (0..1u64 << self.inputs.len())
+            .map(|inputs| {
+                (self.outputs.iter().enumerate())
+                    .filter(|(_, (_, expr))| expr.eval(inputs))
+                    .fold(0, |acc, (idx, _)| acc | 1 << idx)
+            })
+            .collect()
+    }
+
+    /// Adds a chip backed by a truth table to the library.
+    pub fn create_table_chip(
+        &self,
+        library: &mut Library,
+        name: &str,
+        category: &str,
+    ) -> Result<(), String> {
+        check_name(library, name)?;
+        let inputs: Vec<&str> = self.inputs.iter().map(|s| s.as_str()).collect();
+        let outputs: Vec<&str> = self.outputs.iter().map(|(s, _)| s.as_str()).collect();
+        library.add_basic_chip(name, &inputs, &outputs, self.truth_map(), Box::new([]))?;
+        let chip = library.chips.last_mut().unwrap();
+        chip.attrs.category = category.into();
+        Ok(())
+    }
+
+    /// Adds a chip made of the And, Or, Not and (optionally) Xor chips of the library,
+    /// placed in columns by their distance from the inputs.
+    pub fn create_gate_chip(
+        &self,
+        library: &mut Library,
+        name: &str,
+        category: &str,
+    ) -> Result<(), String> {
+        check_name(library, name)?;
+        let mut scene = Scene {
+            save_attrs: ChipAttrs {
+                name: name.into(),
+                category: category.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        for input in &self.inputs {
+            let addr = scene.sim.alloc_node();
+            scene.l_nodes.states.push((addr, input.clone()));
+        }
+
+        let mut builder = GateBuilder {
+            library,
+            scene: &mut scene,
+            columns: vec![],
+        };
+        let mut outputs = vec![];
+        for (_, expr) in &self.outputs {
+            outputs.push(builder.build(expr)?);
+        }
+        let width = builder.columns.len() as f32;
+
+        scene.r_nodes.pos = vec2(COLUMN_W * (width + 1.0), 0.0);
+        for (idx, ((name, _), (src, _))) in self.outputs.iter().zip(outputs).enumerate() {
+            let addr = scene.sim.alloc_node();
+            scene.r_nodes.states.push((addr, name.clone()));
+            connect(&mut scene, src, NodeIdent::RExternal(idx as u32));
+        }
+        for idx in 0..scene.wires.len() {
+            _ = scene.route_wire(idx);
+        }
+
+        library.add_chip(create_chip_from_scene(&scene));
+        Ok(())
+    }
+}
+
+fn check_name(library: &Library, name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err(String::from("the chip needs a name"));
+    }
+    match library.chips.iter().any(|chip| chip.attrs.name == name) {
+        true => Err(format!("a chip named {name:?} already exists")),
+        false => Ok(()),
+    }
+}
+
+/// The horizontal distance between columns of gates.
+const COLUMN_W: f32 = UNIT * 6.0;
+/// The vertical distance between gates in a column.
+const ROW_H: f32 = UNIT * 4.0;
+
+/// Connects `src` to `dst` with a wire.
+fn connect(scene: &mut Scene, src: NodeIdent, dst: NodeIdent) {
+    let (Some(src_info), Some(dst_info)) = (scene.node_info(src), scene.node_info(dst)) else {
+        return;
+    };
+    scene
+        .sim
+        .set_node_src(dst_info.addr, Source::new_addr(src_info.addr));
+    scene.wires.push(Wire::new(src, dst, vec![]));
+}
+
+struct GateBuilder<'a> {
+    library: &'a Library,
+    scene: &'a mut Scene,
+    /// The number of gates placed in each column.
+    columns: Vec<u32>,
+}
+impl GateBuilder<'_> {
+    /// Finds a basic chip by name, with the given number of inputs and one output.
+    fn gate(&self, name: &str, inputs: usize) -> Option<usize> {
+        self.library.chips.iter().position(|chip| {
+            chip.attrs.name.eq_ignore_ascii_case(name)
+                && chip.l_nodes.len() == inputs
+                && chip.r_nodes.len() == 1
+        })
+    }
+
+    /// The position for the next gate in `column`.
+    fn place(&mut self, column: usize) -> Vec2 {
+        if self.columns.len() <= column {
+            self.columns.resize(column + 1, 0);
+        }
+        let row = self.columns[column];
+        self.columns[column] += 1;
+        vec2(COLUMN_W * (column as f32 + 1.0), ROW_H * row as f32)
+    }
+
+    /// Places the gates computing `expr`, returning the pin with the result and it's column.
+    fn build(&mut self, expr: &Expr) -> Result<(NodeIdent, usize), String> {
+        let (name, args): (&str, Vec<&Expr>) = match expr {
+            Expr::Input(idx) => return Ok((NodeIdent::LExternal(*idx as u32), 0)),
+            Expr::Const(value) => {
+                let ty = match value {
+                    true => BuiltinDeviceTy::Power,
+                    false => BuiltinDeviceTy::Ground,
+                };
+                let pos = self.place(0);
+                let device = BuiltinDevice::new(ty, &mut self.scene.sim, pos);
+                let id = self.scene.add_device(device);
+                return Ok((NodeIdent::DeviceR(id, 0), 0));
+            }
+            Expr::Not(a) => ("Not", vec![a]),
+            Expr::And(a, b) => ("And", vec![a, b]),
+            Expr::Or(a, b) => ("Or", vec![a, b]),
+            Expr::Xor(a, b) => match self.gate("Xor", 2) {
+                Some(_) => ("Xor", vec![a, b]),
+                // a ^ b = (a | b) & !(a & b)
+                None => {
+                    let either = Expr::Or(a.clone(), b.clone());
+                    let both = Expr::Not(Box::new(Expr::And(a.clone(), b.clone())));
+                    return self.build(&Expr::And(Box::new(either), Box::new(both)));
+                }
+            },
+        };
+        let save_id = self.gate(name, args.len()).ok_or_else(|| {
+            format!(
+                "the library has no {name} chip with {} inputs and 1 output",
+                args.len()
+            )
+        })?;
+
+        let mut srcs = vec![];
+        for arg in args {
+            srcs.push(self.build(arg)?);
+        }
+        let column = srcs.iter().map(|(_, column)| column + 1).max().unwrap_or(1);
+        let pos = self.place(column);
+        let save = &self.library.chips[save_id];
+        let id = place_chip(
+            self.scene,
+            Some(save_id),
+            save,
+            pos,
+            Default::default(),
+            Default::default(),
+        );
+        for (idx, (src, _)) in srcs.into_iter().enumerate() {
+            connect(self.scene, src, NodeIdent::DeviceL(id, idx as u32));
+        }
+        Ok((NodeIdent::DeviceR(id, 0), column))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::bench::{LiveTable, TestBench};
+    use crate::sim::minimize::{format_sop, minimize_output};
+    use crate::sim::save::StartingChip;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn library(chips: &[StartingChip]) -> Library {
+        let mut library = Library::default();
+        chips.iter().for_each(|chip| chip.create(&mut library));
+        library
+    }
+
+    #[test]
+    fn precedence() {
+        let program = Program::parse("out = a | b & !c ^ d").unwrap();
+        assert_eq!(program.inputs, names(&["a", "b", "c", "d"]));
+        let expected = |i: u64| {
+            let (a, b, c, d) = (i & 1 != 0, i & 2 != 0, i & 4 != 0, i & 8 != 0);
+            a || ((b && !c) != d)
+        };
+        for (row, outputs) in program.truth_map().iter().enumerate() {
+            assert_eq!(*outputs != 0, expected(row as u64), "row {row}");
+        }
+    }
+
+    #[test]
+    fn parentheses_and_aliases() {
+        let a = Program::parse("out = (a + b) * ~c").unwrap();
+        let b = Program::parse("out = (a | b) & !c").unwrap();
+        assert_eq!(a.truth_map(), b.truth_map());
+    }
+
+    #[test]
+    fn outputs_are_substituted() {
+        let program = Program::parse("x = a & b; y = x | c\nz = !x").unwrap();
+        assert_eq!(program.inputs, names(&["a", "b", "c"]));
+        let outputs: Vec<&str> = program.outputs.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(outputs, ["x", "y", "z"]);
+        assert_eq!(
+            program.outputs[1].1,
+            Expr::Or(
+                Box::new(Expr::And(
+                    Box::new(Expr::Input(0)),
+                    Box::new(Expr::Input(1))
+                )),
+                Box::new(Expr::Input(2)),
+            )
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let err = |text: &str| Program::parse(text).unwrap_err();
+        assert_eq!(
+            err(""),
+            "there are no outputs, write one like `out = a & b`"
+        );
+        assert_eq!(err("a & b"), "line 1: expected `output = expression`");
+        assert_eq!(err("out = a $ b"), "line 1: unexpected '$'");
+        assert_eq!(err("out = (a & b"), "line 1: expected `)`");
+        assert_eq!(err("out = a &"), "line 1: the expression ends early");
+        assert_eq!(
+            err("out = a b"),
+            "line 1: unexpected Name(\"b\") after the expression"
+        );
+        assert_eq!(err("1x = a"), "line 1: \"1x\" isn't a valid output name");
+        assert_eq!(err("x = a\nx = b"), "line 2: x is defined more than once");
+        assert_eq!(
+            err("y = x\nx = a"),
+            "line 2: x is used as an input before it's defined"
+        );
+    }
+
+    #[test]
+    fn minimized_round_trip() {
+        let program = Program::parse("s = a ^ b ^ c; c_out = a & b | c & (a ^ b)").unwrap();
+        let map = program.truth_map();
+        for output in 0..program.outputs.len() {
+            let implicants = minimize_output(program.inputs.len(), &map, &[], output);
+            let sop = format_sop(&implicants, &program.inputs);
+            let minimized = Program::parse(&format!("out = {sop}")).unwrap();
+            for (row, outputs) in map.iter().enumerate() {
+                // The minimized sum only uses the inputs it needs, which can be fewer
+                let mut inputs = 0;
+                for (idx, name) in minimized.inputs.iter().enumerate() {
+                    let orig = program.inputs.iter().position(|n| n == name).unwrap();
+                    inputs |= ((row as u64 >> orig) & 1) << idx;
+                }
+                let expected = (outputs >> output) & 1 != 0;
+                assert_eq!(minimized.outputs[0].1.eval(inputs), expected, "{sop}");
+            }
+        }
+    }
+
+    #[test]
+    fn gate_chip_matches_truth_map() {
+        for chips in [
+            &[StartingChip::And, StartingChip::Or, StartingChip::Not][..],
+            &[
+                StartingChip::And,
+                StartingChip::Or,
+                StartingChip::Not,
+                StartingChip::Xor,
+            ],
+        ] {
+            let mut library = library(chips);
+            let program = Program::parse("s = a ^ b ^ c; c_out = a & b | c & (a ^ b)").unwrap();
+            program.create_gate_chip(&mut library, "Adder", "").unwrap();
+
+            let chip = library.chips.last().unwrap();
+            let mut bench = TestBench::from_chip(chip);
+            let table = LiveTable::evaluate(&mut bench, &library.tables, 100).unwrap();
+            assert_eq!(table.map, program.truth_map().to_vec());
+        }
+    }
+
+    #[test]
+    fn gate_chip_needs_gates() {
+        let mut library = library(&[StartingChip::And]);
+        let program = Program::parse("out = !a").unwrap();
+        assert_eq!(
+            program.create_gate_chip(&mut library, "Inv", ""),
+            Err(String::from(
+                "the library has no Not chip with 1 inputs and 1 output"
+            ))
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! Parsing of Intel HEX files, used to load the contents of memory devices.
+
+/// The largest image that will be loaded, so that a far away address doesn't allocate gigabytes.
+const MAX_SIZE: usize = 1 << 24;
+
+/// Parses the text of an Intel HEX file into a binary image starting at address 0.
+/// Gaps between records are filled with zeros.
+pub fn parse(text: &str) -> Result<Vec<u8>, String> {
+    let mut out = vec![];
+    // The upper bits of the address, set by extended address records
+    let mut offset = 0u32;
+
+    for (line_idx, line) in text.lines().enumerate() {
+        let line_num = line_idx + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some(hex) = line.strip_prefix(':') else {
+            return Err(format!("line {line_num}: expected ':'"));
+        };
+        if hex.len() % 2 != 0 || hex.len() < 10 {
+            return Err(format!("line {line_num}: invalid record length"));
+        }
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|idx| u8::from_str_radix(&hex[idx..idx + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| format!("line {line_num}: invalid hex digit"))?;
+
+        let len = bytes[0] as usize;
+        if bytes.len() != len + 5 {
+            return Err(format!("line {line_num}: byte count doesn't match record"));
+        }
+        let sum = bytes.iter().fold(0u8, |acc, byte| acc.wrapping_add(*byte));
+        if sum != 0 {
+            return Err(format!("line {line_num}: checksum mismatch"));
+        }
+        let addr = u16::from_be_bytes([bytes[1], bytes[2]]) as u32;
+        let data = &bytes[4..4 + len];
+
+        match bytes[3] {
+            // Data
+            0x00 => {
+                let start = (offset + addr) as usize;
+                if start + len > MAX_SIZE {
+                    return Err(format!("line {line_num}: address {start:#X} is too large"));
+                }
+                if out.len() < start + len {
+                    out.resize(start + len, 0);
+                }
+                out[start..start + len].copy_from_slice(data);
+            }
+            // End of file
+            0x01 => break,
+            // Extended segment address
+            0x02 if len == 2 => offset = (u16::from_be_bytes([data[0], data[1]]) as u32) << 4,
+            // Extended linear address
+            0x04 if len == 2 => offset = (u16::from_be_bytes([data[0], data[1]]) as u32) << 16,
+            // Start addresses don't matter for memory contents
+            0x03 | 0x05 => {}
+            ty => return Err(format!("line {line_num}: unsupported record type {ty:02X}")),
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Formats a record, with it's checksum.
+    fn record(ty: u8, addr: u16, data: &[u8]) -> String {
+        let mut bytes = vec![data.len() as u8];
+        bytes.extend(addr.to_be_bytes());
+        bytes.push(ty);
+        bytes.extend(data);
+        let sum = bytes.iter().fold(0u8, |acc, byte| acc.wrapping_add(*byte));
+        bytes.push(sum.wrapping_neg());
+        let hex: String = bytes.iter().map(|byte| format!("{byte:02X}")).collect();
+        format!(":{hex}\n")
+    }
+
+    #[test]
+    fn data() {
+        let text = record(0x00, 0x0002, &[0xAB, 0xCD]) + &record(0x01, 0, &[]);
+        assert_eq!(parse(&text).unwrap(), [0, 0, 0xAB, 0xCD]);
+    }
+
+    #[test]
+    fn checksum() {
+        assert_eq!(
+            parse(":0100000001FF\n"),
+            Err(String::from("line 1: checksum mismatch"))
+        );
+        assert_eq!(parse(":0100000001FE\n").unwrap(), [0x01]);
+    }
+
+    #[test]
+    fn extended_addresses() {
+        // Segment addresses are shifted by 4 bits
+        let text = record(0x02, 0, &[0x00, 0x01]) + &record(0x00, 0x0001, &[0x11]);
+        let image = parse(&text).unwrap();
+        assert_eq!((image.len(), image[0x11]), (0x12, 0x11));
+
+        // Linear addresses are shifted by 16 bits
+        let text = record(0x04, 0, &[0x00, 0x01]) + &record(0x00, 0x0002, &[0x22]);
+        let image = parse(&text).unwrap();
+        assert_eq!((image.len(), image[0x1_0002]), (0x1_0003, 0x22));
+
+        // Start addresses are ignored
+        let text = record(0x05, 0, &[0, 0, 0x10, 0]) + &record(0x00, 0, &[0x33]);
+        assert_eq!(parse(&text).unwrap(), [0x33]);
+    }
+
+    #[test]
+    fn end_of_file() {
+        // Nothing after the end of file record is read, even if it's invalid
+        let text = record(0x00, 0, &[0x44]) + &record(0x01, 0, &[]) + "garbage\n";
+        assert_eq!(parse(&text).unwrap(), [0x44]);
+        assert_eq!(parse(&record(0x01, 0, &[])).unwrap(), []);
+    }
+
+    #[test]
+    fn max_size() {
+        // The last byte that fits
+        let text = record(0x04, 0, &[0x00, 0xFF]) + &record(0x00, 0xFFFF, &[0x55]);
+        assert_eq!(parse(&text).unwrap().len(), MAX_SIZE);
+
+        let text = record(0x04, 0, &[0x01, 0x00]) + &record(0x00, 0, &[0x55]);
+        assert_eq!(
+            parse(&text),
+            Err(String::from("line 2: address 0x1000000 is too large"))
+        );
+    }
+}
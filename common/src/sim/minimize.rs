@@ -0,0 +1,239 @@
+//! Minimizing truth tables into sums of products, with the Quine–McCluskey method.
+
+use std::collections::HashSet;
+
+/// A product of inputs, that is true for every combination of inputs it covers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Implicant {
+    /// The values of the inputs in the product.
+    pub value: u64,
+    /// The inputs that aren't in the product.
+    pub mask: u64,
+}
+impl Implicant {
+    pub fn covers(self, minterm: u64) -> bool {
+        minterm & !self.mask == self.value
+    }
+
+    /// The number of inputs in the product.
+    pub fn literals(self, num_inputs: usize) -> u32 {
+        num_inputs as u32 - self.mask.count_ones()
+    }
+
+    /// Formats the product like the expression parser reads it, e.g. `a & !b`.
+    pub fn format(self, names: &[String]) -> String {
+        let literals: Vec<String> = (names.iter().enumerate())
+            .filter(|(idx, _)| self.mask & (1 << idx) == 0)
+            .map(|(idx, name)| match self.value & (1 << idx) != 0 {
+                true => name.clone(),
+                false => format!("!{name}"),
+            })
+            .collect();
+        match literals.is_empty() {
+            true => String::from("1"),
+            false => literals.join(" & "),
+        }
+    }
+}
+
+/// The implicants that can't be combined into larger ones.
+pub fn prime_implicants(num_inputs: usize, terms: &[u64]) -> Vec<Implicant> {
+    let mut primes: Vec<Implicant> = vec![];
+    let mut level: HashSet<Implicant> = (terms.iter())
+        .map(|value| Implicant {
+            value: *value,
+            mask: 0,
+        })
+        .collect();
+    while !level.is_empty() {
+        let mut next = HashSet::new();
+        let mut combined = HashSet::new();
+        for implicant in &level {
+            for bit in (0..num_inputs).map(|idx| 1u64 << idx) {
+                if implicant.mask & bit != 0 || implicant.value & bit != 0 {
+                    continue;
+                }
+                // Two implicants that only differ in one input combine into one without it
+                let other = Implicant {
+                    value: implicant.value | bit,
+                    mask: implicant.mask,
+                };
+                if level.contains(&other) {
+                    combined.insert(*implicant);
+                    combined.insert(other);
+                    next.insert(Implicant {
+                        value: implicant.value,
+                        mask: implicant.mask | bit,
+                    });
+                }
+            }
+        }
+        primes.extend(level.difference(&combined));
+        level = next;
+    }
+    primes.sort_by_key(|prime| (prime.mask, prime.value));
+    primes
+}
+
+/// Finds a small set of products that covers every minterm, and no combination outside of
+/// `minterms` and `dont_cares`. Essential prime implicants are picked first, then the rest are
+/// picked greedily, so the result is minimal in most but not all cases.
+pub fn minimize(num_inputs: usize, minterms: &[u64], dont_cares: &[u64]) -> Vec<Implicant> {
+    let terms: Vec<u64> = minterms.iter().chain(dont_cares).copied().collect();
+    let primes = prime_implicants(num_inputs, &terms);
+    let mut uncovered: Vec<u64> = minterms.to_vec();
+    let mut picked: Vec<Implicant> = vec![];
+
+    for minterm in minterms {
+        let mut covering = primes.iter().filter(|prime| prime.covers(*minterm));
+        if let (Some(prime), None) = (covering.next(), covering.next()) {
+            if !picked.contains(prime) {
+                picked.push(*prime);
+            }
+        }
+    }
+    uncovered.retain(|minterm| !picked.iter().any(|prime| prime.covers(*minterm)));
+
+    while !uncovered.is_empty() {
+        let best = (primes.iter())
+            .filter(|prime| !picked.contains(prime))
+            .max_by_key(|prime| {
+                let count = uncovered.iter().filter(|m| prime.covers(**m)).count();
+                (count, std::cmp::Reverse(prime.literals(num_inputs)))
+            })
+            .copied()
+            .expect("every minterm is covered by a prime implicant");
+        picked.push(best);
+        uncovered.retain(|minterm| !best.covers(*minterm));
+    }
+    picked.sort_by_key(|prime| (prime.mask, prime.value));
+    picked
+}
+
+/// Minimizes one output of a table, indexed like [crate::sim::TruthTable::map].
+/// The rows `dont_care` has the output's bit set in can have either value.
+pub fn minimize_output(
+    num_inputs: usize,
+    map: &[u64],
+    dont_care: &[u64],
+    output: usize,
+) -> Vec<Implicant> {
+    let bit = 1 << output;
+    let is_dont_care = |row: usize| dont_care.get(row).is_some_and(|dc| dc & bit != 0);
+    let minterms: Vec<u64> = (0..map.len())
+        .filter(|row| map[*row] & bit != 0 && !is_dont_care(*row))
+        .map(|row| row as u64)
+        .collect();
+    let dont_cares: Vec<u64> = (0..map.len())
+        .filter(|row| is_dont_care(*row))
+        .map(|row| row as u64)
+        .collect();
+    minimize(num_inputs, &minterms, &dont_cares)
+}
+
+/// Formats a sum of products like the expression parser reads it, e.g. `a & !b | c`.
+pub fn format_sop(implicants: &[Implicant], names: &[String]) -> String {
+    match implicants.is_empty() {
+        true => String::from("0"),
+        false => (implicants.iter())
+            .map(|implicant| implicant.format(names))
+            .collect::<Vec<_>>()
+            .join(" | "),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn covers_exactly(implicants: &[Implicant], num_inputs: usize, map: &[u64], dont_care: &[u64]) {
+        for row in 0..1u64 << num_inputs {
+            if dont_care.get(row as usize).is_some_and(|dc| *dc != 0) {
+                continue;
+            }
+            let covered = implicants.iter().any(|imp| imp.covers(row));
+            assert_eq!(covered, map[row as usize] != 0, "row {row}");
+        }
+    }
+
+    #[test]
+    fn combines_adjacent_terms() {
+        // a & b | a & !b = a
+        let primes = prime_implicants(2, &[0b01, 0b11]);
+        assert_eq!(
+            primes,
+            [Implicant {
+                value: 0b01,
+                mask: 0b10
+            }]
+        );
+    }
+
+    #[test]
+    fn constant_outputs() {
+        assert_eq!(format_sop(&minimize(2, &[], &[]), &names(&["a", "b"])), "0");
+        let all = minimize(2, &[0, 1, 2, 3], &[]);
+        assert_eq!(format_sop(&all, &names(&["a", "b"])), "1");
+    }
+
+    #[test]
+    fn formats_products() {
+        let names = names(&["a", "b", "c"]);
+        let implicants = [
+            Implicant {
+                value: 0b001,
+                mask: 0b100,
+            },
+            Implicant {
+                value: 0b100,
+                mask: 0b011,
+            },
+        ];
+        assert_eq!(format_sop(&implicants, &names), "a & !b | c");
+    }
+
+    #[test]
+    fn uses_dont_cares() {
+        // Only row 3 has to be true, but rows 1 and 2 can be used to make `a | b`
+        let map = [0, 0, 0, 1];
+        let dont_care = [0, 1, 1, 0];
+        let implicants = minimize_output(2, &map, &dont_care, 0);
+        assert_eq!(implicants.len(), 1);
+        assert_eq!(implicants[0].literals(2), 1);
+        covers_exactly(&implicants, 2, &map, &dont_care);
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let mut rng = fastrand::Rng::with_seed(7);
+        for _ in 0..200 {
+            let num_inputs = rng.usize(1..=5);
+            let rows = 1 << num_inputs;
+            let map: Vec<u64> = (0..rows).map(|_| rng.u64(0..2)).collect();
+            let dont_care: Vec<u64> = (0..rows).map(|_| (rng.u8(0..8) == 0) as u64).collect();
+            let implicants = minimize_output(num_inputs, &map, &dont_care, 0);
+            covers_exactly(&implicants, num_inputs, &map, &dont_care);
+
+            // Every product is prime, so no input can be dropped from it
+            for imp in &implicants {
+                for bit in (0..num_inputs).map(|idx| 1u64 << idx) {
+                    if imp.mask & bit != 0 {
+                        continue;
+                    }
+                    let larger = Implicant {
+                        value: imp.value & !bit,
+                        mask: imp.mask | bit,
+                    };
+                    let allowed = (0..rows as u64)
+                        .filter(|row| larger.covers(*row))
+                        .all(|row| map[row as usize] != 0 || dont_care[row as usize] != 0);
+                    assert!(!allowed, "{imp:?} isn't prime");
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,17 @@
+pub mod bench;
+pub mod debug;
+pub mod expr;
+pub mod ihex;
+pub mod minimize;
+pub mod native;
+pub mod route;
 pub mod save;
 pub mod scene;
+pub mod snapshot;
+pub mod vector;
+pub mod wave;
 
+use native::NativeDevice;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -79,6 +90,13 @@ impl SourceTy {
     pub const OP: Self = Self(3);
 }
 
+/// ### Representation:
+/// - bytes 0..4: addr
+/// - bits 32..36: shift, how far the state is shifted right (or left for a gather)
+/// - bits 36..40: width, the number of bits that are kept after shifting (0 = all of them)
+/// - bits 40..44: gather count, the number of nodes from `addr` whose lowest bits are combined
+///   into the state (0 = only copy the node at `addr`)
+///
 #[derive(Clone, Copy)]
 #[repr(C)]
 pub struct CopySource(u64);
@@ -87,6 +105,55 @@ impl CopySource {
     pub const fn new(addr: NodeAddr) -> Self {
         Self(addr.0 as u64)
     }
+    /// Copies `width` bits starting at bit `shift` of the state of `addr`.
+    #[inline(always)]
+    pub const fn new_bits(addr: NodeAddr, shift: u8, width: u8) -> Self {
+        Self(((width as u64 & 0xF) << 36) | ((shift as u64 & 0xF) << 32) | addr.0 as u64)
+    }
+    /// Combines the lowest bits of the `count` nodes starting at `addr` into one state,
+    /// shifted left by `shift`.
+    #[inline(always)]
+    pub const fn new_gather(addr: NodeAddr, count: u8, shift: u8) -> Self {
+        Self(((count as u64 & 0xF) << 40) | ((shift as u64 & 0xF) << 32) | addr.0 as u64)
+    }
+
+    #[inline(always)]
+    pub const fn shift(&self) -> u8 {
+        ((self.0 >> 32) & 0xF) as u8
+    }
+    #[inline(always)]
+    pub const fn width(&self) -> u8 {
+        ((self.0 >> 36) & 0xF) as u8
+    }
+    #[inline(always)]
+    pub const fn count(&self) -> u8 {
+        ((self.0 >> 40) & 0xF) as u8
+    }
+
+    /// Computes the state of a node with this source.
+    #[inline(always)]
+    pub fn eval(&self, nodes: &[Node]) -> u8 {
+        let addr = self.addr().0 as usize;
+        match self.count() {
+            0 => {
+                let state = nodes[addr]
+                    .state()
+                    .checked_shr(self.shift() as u32)
+                    .unwrap_or(0);
+                match self.width() {
+                    0 => state,
+                    width => state & (0xFFu8 >> (8 - width.min(8))),
+                }
+            }
+            count => {
+                let mut state = 0u8;
+                for (idx, node) in nodes[addr..addr + count as usize].iter().enumerate() {
+                    state |= (node.state() & 1) << idx;
+                }
+                state.checked_shl(self.shift() as u32).unwrap_or(0)
+            }
+        }
+    }
 
     #[inline(always)]
     pub const fn addr(&self) -> NodeAddr {
@@ -158,6 +225,10 @@ impl Source {
     pub const fn new_addr(addr: NodeAddr) -> Self {
         Self(((SourceTy::COPY.0 as u64) << 48) | addr.0 as u64)
     }
+    #[inline(always)]
+    pub const fn new_copy(copy: CopySource) -> Self {
+        Self(((SourceTy::COPY.0 as u64) << 48) | (copy.0 & 0x0000FFFFFFFFFFFF))
+    }
 
     #[inline(always)]
     pub const fn ty(&self) -> SourceTy {
@@ -217,18 +288,28 @@ pub struct TruthTable {
     pub num_outputs: u8,
     pub name: String,
     pub map: Box<[u64]>,
+    /// The outputs of each row that can have either value, indexed like `map`.
+    /// Empty if every output is specified.
+    pub dont_care: Box<[u64]>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Sim {
     pub nodes: Vec<Node>,
     pub next_region: u32,
+    pub natives: Vec<NativeDevice>,
+    /// The number of times the simulation has been updated.
+    pub tick: u64,
+    pub recorder: wave::Recorder,
 }
 impl Default for Sim {
     fn default() -> Self {
         Self {
             nodes: vec![Node::default()],
             next_region: 1,
+            natives: vec![],
+            tick: 0,
+            recorder: Default::default(),
         }
     }
 }
@@ -236,6 +317,16 @@ impl Sim {
     pub fn clear(&mut self) {
         self.nodes = vec![Node::default()];
         self.next_region = 1;
+        self.natives.clear();
+        self.tick = 0;
+        self.recorder = Default::default();
+    }
+
+    pub fn native(&self, base: NodeAddr) -> Option<&NativeDevice> {
+        self.natives.iter().find(|native| native.base() == base)
+    }
+    pub fn native_mut(&mut self, base: NodeAddr) -> Option<&mut NativeDevice> {
+        self.natives.iter_mut().find(|native| native.base() == base)
     }
 
     pub fn set_node_src(&mut self, addr: NodeAddr, src: Source) {
@@ -273,12 +364,19 @@ impl Sim {
         }
     }
 
+    /// Frees `region` if it's the last one that was allocated. Earlier regions stay allocated, as
+    /// the ones after them would have to move.
+    pub fn free_region(&mut self, region: &NodeRegion) {
+        if region.max.0 == self.next_region {
+            self.next_region = region.min.0;
+            self.nodes.truncate(region.min.0 as usize);
+        }
+    }
+
     fn update_node(node: Node, out: &mut Node, nodes: &[Node], tables: &[TruthTable]) {
         match node.source().ty() {
             SourceTy::NONE => {}
-            SourceTy::COPY => {
-                out.set_state(nodes[node.source().as_copy().addr().0 as usize].state())
-            }
+            SourceTy::COPY => out.set_state(node.source().as_copy().eval(nodes)),
             SourceTy::TABLE => {
                 let table_src = node.source().as_table();
 
@@ -287,7 +385,8 @@ impl Sim {
                     ..(table_src.inputs().0 as usize + table.num_inputs as usize)];
                 let mut input: u32 = 0;
                 for (idx, node) in input_nodes.iter().enumerate() {
-                    input |= (node.state() as u32) << idx as u32;
+                    // A bus can be wired into a table input, which only reads it's lowest bit
+                    input |= (node.state() as u32 & 1) << idx as u32;
                 }
                 let output = table.map[input as usize];
                 let x = table_src.output() as u64;
@@ -305,6 +404,12 @@ impl Sim {
             Self::update_node(*node, &mut new_nodes[idx], &self.nodes, tables);
         }
         self.nodes = new_nodes;
+
+        for native in &mut self.natives {
+            native.update(&mut self.nodes);
+        }
+        self.recorder.record(self.tick, &self.nodes);
+        self.tick += 1;
     }
 
     pub fn into_chip(&self) -> save::ChipSave {
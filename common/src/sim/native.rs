@@ -0,0 +1,603 @@
+//! Devices with behavior that can't be expressed with node sources.
+//! They are updated by the [Sim](crate::sim::Sim) after the nodes every tick.
+
+use crate::sim::{Node, NodeAddr, NodeRegion};
+use serde::{Deserialize, Serialize};
+
+use std::collections::VecDeque;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum NativeDevice {
+    Clock(Clock),
+    PixelDisplay(PixelDisplay),
+    Rom(Rom),
+    Ram(Ram),
+    Keyboard(Keyboard),
+    Terminal(Terminal),
+    Probe(Probe),
+}
+impl NativeDevice {
+    /// The first node of the device, used to find the device a scene device belongs to.
+    pub fn base(&self) -> NodeAddr {
+        match self {
+            Self::Clock(clock) => clock.out,
+            Self::PixelDisplay(display) => display.base,
+            Self::Rom(rom) => rom.base,
+            Self::Ram(ram) => ram.base,
+            Self::Keyboard(keyboard) => keyboard.base,
+            Self::Terminal(terminal) => terminal.base,
+            Self::Probe(probe) => probe.input,
+        }
+    }
+
+    /// Moves all of the nodes of the device into `region`.
+    pub fn map(&self, region: &NodeRegion) -> Self {
+        match self {
+            Self::Clock(clock) => Self::Clock(Clock {
+                out: region.map(clock.out),
+                ..clock.clone()
+            }),
+            Self::PixelDisplay(display) => Self::PixelDisplay(PixelDisplay {
+                base: region.map(display.base),
+                ..display.clone()
+            }),
+            Self::Rom(rom) => Self::Rom(Rom {
+                base: region.map(rom.base),
+                ..rom.clone()
+            }),
+            Self::Ram(ram) => Self::Ram(Ram {
+                base: region.map(ram.base),
+                ..ram.clone()
+            }),
+            Self::Keyboard(keyboard) => Self::Keyboard(Keyboard {
+                base: region.map(keyboard.base),
+                ..keyboard.clone()
+            }),
+            Self::Terminal(terminal) => Self::Terminal(Terminal {
+                base: region.map(terminal.base),
+                ..terminal.clone()
+            }),
+            Self::Probe(probe) => Self::Probe(Probe {
+                input: region.map(probe.input),
+                ..probe.clone()
+            }),
+        }
+    }
+
+    /// Copies the state of `old` that is still valid after the device was reconfigured.
+    pub fn restore(&mut self, old: &Self) {
+        match (self, old) {
+            (Self::Clock(clock), Self::Clock(old)) => {
+                clock.period = old.period;
+                clock.duty = old.duty;
+                clock.paused = old.paused;
+            }
+            (Self::Rom(rom), Self::Rom(old)) => {
+                let mask = rom.config.mask();
+                for (word, old) in rom.data.iter_mut().zip(&old.data) {
+                    *word = old & mask;
+                }
+            }
+            (Self::Ram(ram), Self::Ram(old)) => {
+                let mask = ram.config.mask();
+                for (word, old) in ram.data.iter_mut().zip(&old.data) {
+                    *word = old & mask;
+                }
+                ram.persist = old.persist;
+            }
+            _ => {}
+        }
+    }
+
+    /// Resets the state that shouldn't be kept when a project is loaded.
+    pub fn on_load(&mut self) {
+        if let Self::Ram(ram) = self {
+            if !ram.persist {
+                ram.data.fill(0);
+            }
+        }
+    }
+
+    pub fn update(&mut self, nodes: &mut [Node]) {
+        match self {
+            Self::Clock(clock) => clock.update(nodes),
+            Self::PixelDisplay(display) => display.update(nodes),
+            Self::Rom(rom) => rom.update(nodes),
+            Self::Ram(ram) => ram.update(nodes),
+            Self::Keyboard(keyboard) => keyboard.update(nodes),
+            Self::Terminal(terminal) => terminal.update(nodes),
+            Self::Probe(probe) => probe.update(nodes),
+        }
+    }
+}
+
+/// Outputs a square wave, measured in simulation ticks.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Clock {
+    pub out: NodeAddr,
+    /// The number of ticks in one cycle.
+    pub period: u32,
+    /// The number of ticks the output is high each cycle.
+    pub duty: u32,
+    pub paused: bool,
+    /// The current tick within the cycle.
+    pub phase: u32,
+}
+impl Clock {
+    pub fn new(out: NodeAddr) -> Self {
+        Self {
+            out,
+            period: 20,
+            duty: 10,
+            paused: false,
+            phase: 0,
+        }
+    }
+
+    pub fn high(&self) -> bool {
+        self.phase < self.duty
+    }
+
+    /// Advances the clock to it's next edge.
+    pub fn step(&mut self) {
+        let high = self.high();
+        for _ in 0..self.period.max(1) {
+            self.phase = (self.phase + 1) % self.period.max(1);
+            if self.high() != high {
+                break;
+            }
+        }
+    }
+
+    fn update(&mut self, nodes: &mut [Node]) {
+        if !self.paused {
+            self.phase = (self.phase + 1) % self.period.max(1);
+        }
+        nodes[self.out.0 as usize].set_state(self.high() as u8);
+    }
+}
+
+/// The number of address bits needed to select one of `n` items.
+pub fn addr_bits(n: u32) -> u32 {
+    32 - n.max(1).saturating_sub(1).leading_zeros()
+}
+
+/// Reads `count` nodes starting at `addr` as the bits of a number, least significant first.
+fn read_bits(nodes: &[Node], addr: NodeAddr, count: u32) -> u32 {
+    let mut out = 0;
+    for idx in 0..count {
+        out |= ((nodes[(addr.0 + idx) as usize].state() != 0) as u32) << idx;
+    }
+    out
+}
+
+/// Sets `count` nodes starting at `addr` to the bits of `value`, least significant first.
+fn write_bits(nodes: &mut [Node], addr: NodeAddr, count: u32, value: u32) {
+    for idx in 0..count {
+        nodes[(addr.0 + idx) as usize].set_state(((value >> idx) & 1) as u8);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DisplayConfig {
+    pub width: u32,
+    pub height: u32,
+    /// If pixels are only written on the rising edge of a write pin,
+    /// instead of every tick.
+    pub strobe: bool,
+    /// If the display has a pin that clears all of it's pixels.
+    pub clear: bool,
+}
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            width: 8,
+            height: 8,
+            strobe: true,
+            clear: true,
+        }
+    }
+}
+impl DisplayConfig {
+    /// The names of the input pins of the display, in order.
+    pub fn input_names(&self) -> Vec<String> {
+        let mut out = vec![];
+        out.extend((0..addr_bits(self.width)).map(|idx| format!("x{idx}")));
+        out.extend((0..addr_bits(self.height)).map(|idx| format!("y{idx}")));
+        out.push(String::from("data"));
+        if self.strobe {
+            out.push(String::from("write"));
+        }
+        if self.clear {
+            out.push(String::from("clear"));
+        }
+        out
+    }
+}
+
+/// A grid of pixels that are written one at a time.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PixelDisplay {
+    /// The first input pin of the display.
+    pub base: NodeAddr,
+    pub config: DisplayConfig,
+    /// The state of each pixel, row by row.
+    pub pixels: Vec<bool>,
+    pub prev_write: bool,
+}
+impl PixelDisplay {
+    pub fn new(base: NodeAddr, config: DisplayConfig) -> Self {
+        Self {
+            base,
+            config,
+            pixels: vec![false; (config.width * config.height) as usize],
+            prev_write: false,
+        }
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> bool {
+        self.pixels[(y * self.config.width + x) as usize]
+    }
+
+    fn update(&mut self, nodes: &mut [Node]) {
+        let cfg = self.config;
+        let (x_bits, y_bits) = (addr_bits(cfg.width), addr_bits(cfg.height));
+        let mut addr = self.base;
+        let mut next = |count: u32| {
+            let value = read_bits(nodes, addr, count);
+            addr.0 += count;
+            value
+        };
+        let x = next(x_bits);
+        let y = next(y_bits);
+        let data = next(1) != 0;
+        let write = if cfg.strobe { next(1) != 0 } else { true };
+        let clear = cfg.clear && next(1) != 0;
+
+        if clear {
+            self.pixels.fill(false);
+        }
+        let rising = write && !(cfg.strobe && self.prev_write);
+        if rising && x < cfg.width && y < cfg.height {
+            self.pixels[(y * cfg.width + x) as usize] = data;
+        }
+        self.prev_write = write;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MemoryConfig {
+    pub addr_bits: u32,
+    pub data_bits: u32,
+}
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        Self {
+            addr_bits: 4,
+            data_bits: 8,
+        }
+    }
+}
+impl MemoryConfig {
+    /// The number of words in the memory.
+    pub fn words(&self) -> usize {
+        1 << self.addr_bits
+    }
+
+    /// The bits of a word that are stored.
+    pub fn mask(&self) -> u32 {
+        u32::MAX >> (32 - self.data_bits.clamp(1, 32))
+    }
+
+    /// The number of bytes each word takes up in a binary file.
+    pub fn word_bytes(&self) -> usize {
+        self.data_bits.div_ceil(8) as usize
+    }
+
+    /// Converts the little-endian words in `bytes` to the contents of a memory.
+    /// Missing words are zero, and extra bytes are ignored.
+    pub fn words_from_bytes(&self, bytes: &[u8]) -> Vec<u32> {
+        let mut out = vec![0; self.words()];
+        for (word, chunk) in out.iter_mut().zip(bytes.chunks(self.word_bytes())) {
+            let value = chunk
+                .iter()
+                .rev()
+                .fold(0u32, |acc, byte| (acc << 8) | *byte as u32);
+            *word = value & self.mask();
+        }
+        out
+    }
+}
+
+/// Read only memory, outputs the word at the address on it's input pins.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Rom {
+    /// The first address pin, followed by the rest of the address pins and then the data pins.
+    pub base: NodeAddr,
+    pub config: MemoryConfig,
+    pub data: Vec<u32>,
+}
+impl Rom {
+    pub fn new(base: NodeAddr, config: MemoryConfig) -> Self {
+        Self {
+            base,
+            config,
+            data: vec![0; config.words()],
+        }
+    }
+
+    fn update(&mut self, nodes: &mut [Node]) {
+        let cfg = self.config;
+        let addr = read_bits(nodes, self.base, cfg.addr_bits) as usize;
+        let word = self.data.get(addr).copied().unwrap_or(0);
+        let out = NodeAddr(self.base.0 + cfg.addr_bits);
+        write_bits(nodes, out, cfg.data_bits, word);
+    }
+}
+
+/// Memory that can be written on the rising edge of it's clock.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Ram {
+    /// The first address pin, followed by the rest of the address pins, the data in pins,
+    /// write enable, clock and then the data out pins.
+    pub base: NodeAddr,
+    pub config: MemoryConfig,
+    pub data: Vec<u32>,
+    /// If the contents are kept when the project is loaded, otherwise they're cleared.
+    pub persist: bool,
+    pub prev_clk: bool,
+}
+impl Ram {
+    pub fn new(base: NodeAddr, config: MemoryConfig) -> Self {
+        Self {
+            base,
+            config,
+            data: vec![0; config.words()],
+            persist: false,
+            prev_clk: false,
+        }
+    }
+
+    fn update(&mut self, nodes: &mut [Node]) {
+        let cfg = self.config;
+        let mut addr = self.base;
+        let mut next = |count: u32| {
+            let value = read_bits(nodes, addr, count);
+            addr.0 += count;
+            value
+        };
+        let word_addr = next(cfg.addr_bits) as usize;
+        let din = next(cfg.data_bits);
+        let we = next(1) != 0;
+        let clk = next(1) != 0;
+
+        if we && clk && !self.prev_clk {
+            if let Some(word) = self.data.get_mut(word_addr) {
+                *word = din & cfg.mask();
+            }
+        }
+        self.prev_clk = clk;
+
+        let word = self.data.get(word_addr).copied().unwrap_or(0);
+        write_bits(nodes, addr, cfg.data_bits, word);
+    }
+}
+
+/// Outputs the ASCII code of the last typed key, and a flag that is set until it's acknowledged.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Keyboard {
+    /// The acknowledge pin, followed by the 7 code pins and the ready pin.
+    pub base: NodeAddr,
+    pub code: u8,
+    pub ready: bool,
+    pub prev_ack: bool,
+}
+impl Keyboard {
+    pub const CODE_BITS: u32 = 7;
+
+    pub fn new(base: NodeAddr) -> Self {
+        Self {
+            base,
+            code: 0,
+            ready: false,
+            prev_ack: false,
+        }
+    }
+
+    /// Called when a key is typed, ignores characters that aren't ASCII.
+    pub fn push(&mut self, c: char) {
+        if c.is_ascii() {
+            self.code = c as u8;
+            self.ready = true;
+        }
+    }
+
+    fn update(&mut self, nodes: &mut [Node]) {
+        let ack = nodes[self.base.0 as usize].state() != 0;
+        if ack && !self.prev_ack {
+            self.ready = false;
+        }
+        self.prev_ack = ack;
+
+        let code = NodeAddr(self.base.0 + 1);
+        write_bits(nodes, code, Self::CODE_BITS, self.code as u32);
+        let ready = (self.base.0 + 1 + Self::CODE_BITS) as usize;
+        nodes[ready].set_state(self.ready as u8);
+    }
+}
+
+/// Shows text that is written one character at a time.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Terminal {
+    /// The first of the 7 character pins, followed by the write pin.
+    pub base: NodeAddr,
+    pub text: String,
+    pub prev_write: bool,
+}
+impl Terminal {
+    pub const CHAR_BITS: u32 = 7;
+    /// The number of characters kept before the oldest are removed.
+    pub const MAX_LEN: usize = 4096;
+
+    pub fn new(base: NodeAddr) -> Self {
+        Self {
+            base,
+            text: String::new(),
+            prev_write: false,
+        }
+    }
+
+    fn update(&mut self, nodes: &mut [Node]) {
+        let code = read_bits(nodes, self.base, Self::CHAR_BITS) as u8;
+        let write = nodes[(self.base.0 + Self::CHAR_BITS) as usize].state() != 0;
+        if write && !self.prev_write {
+            match code {
+                // backspace
+                0x08 => _ = self.text.pop(),
+                b'\n' | b'\t' | 0x20..=0x7E => self.text.push(code as char),
+                b'\r' => self.text.push('\n'),
+                _ => {}
+            }
+            if self.text.len() > Self::MAX_LEN {
+                self.text.drain(..self.text.len() - Self::MAX_LEN);
+            }
+        }
+        self.prev_write = write;
+    }
+}
+
+/// Records the state of a node over the last ticks, to be shown as a timing trace.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Probe {
+    pub input: NodeAddr,
+    /// The number of ticks that are kept.
+    pub depth: u32,
+    /// If a trace only starts on a rising edge of the input and stops once it's full,
+    /// instead of scrolling every tick.
+    pub trigger: bool,
+    pub samples: VecDeque<u8>,
+    /// If a triggered trace is being recorded.
+    pub capturing: bool,
+    pub prev: u8,
+}
+impl Probe {
+    pub fn new(input: NodeAddr) -> Self {
+        Self {
+            input,
+            depth: 100,
+            trigger: false,
+            samples: VecDeque::new(),
+            capturing: false,
+            prev: 0,
+        }
+    }
+
+    fn update(&mut self, nodes: &mut [Node]) {
+        let state = nodes[self.input.0 as usize].state();
+        let depth = self.depth.max(1) as usize;
+        if self.trigger {
+            if !self.capturing && state != 0 && self.prev == 0 {
+                self.samples.clear();
+                self.capturing = true;
+            }
+            if self.capturing {
+                self.samples.push_back(state);
+                self.capturing = self.samples.len() < depth;
+            }
+        } else {
+            self.samples.push_back(state);
+        }
+        while self.samples.len() > depth {
+            self.samples.pop_front();
+        }
+        self.prev = state;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clock_period() {
+        let mut nodes = vec![Node::default()];
+        let mut clock = Clock::new(NodeAddr(0));
+        (clock.period, clock.duty) = (4, 1);
+        let states: Vec<u8> = (0..8)
+            .map(|_| {
+                clock.update(&mut nodes);
+                nodes[0].state()
+            })
+            .collect();
+        assert_eq!(states, [0, 0, 0, 1, 0, 0, 0, 1]);
+
+        // Paused clocks keep their output
+        clock.paused = true;
+        clock.update(&mut nodes);
+        assert_eq!(nodes[0].state(), 1);
+    }
+
+    #[test]
+    fn ram_write_read() {
+        let config = MemoryConfig {
+            addr_bits: 2,
+            data_bits: 4,
+        };
+        // Address 0..2, data in 2..6, write enable 6, clock 7 and data out 8..12
+        let mut nodes = vec![Node::default(); 12];
+        let mut ram = Ram::new(NodeAddr(0), config);
+        let mut tick = |nodes: &mut Vec<Node>, addr, din, we, clk| {
+            write_bits(nodes, NodeAddr(0), 2, addr);
+            write_bits(nodes, NodeAddr(2), 4, din);
+            write_bits(nodes, NodeAddr(6), 1, we);
+            write_bits(nodes, NodeAddr(7), 1, clk);
+            ram.update(nodes);
+            read_bits(nodes, NodeAddr(8), 4)
+        };
+
+        // Writes only happen on the rising edge of the clock
+        assert_eq!(tick(&mut nodes, 2, 0b1010, 1, 0), 0);
+        assert_eq!(tick(&mut nodes, 2, 0b1010, 1, 1), 0b1010);
+        assert_eq!(tick(&mut nodes, 2, 0b0101, 1, 1), 0b1010);
+        // Nothing is written without write enable
+        assert_eq!(tick(&mut nodes, 1, 0b0110, 0, 0), 0);
+        assert_eq!(tick(&mut nodes, 1, 0b0110, 0, 1), 0);
+        assert_eq!(tick(&mut nodes, 2, 0, 0, 0), 0b1010);
+        assert_eq!(ram.data, [0, 0, 0b1010, 0]);
+    }
+
+    #[test]
+    fn keyboard_handshake() {
+        // Acknowledge 0, code 1..8 and ready 8
+        let mut nodes = vec![Node::default(); 9];
+        let mut keyboard = Keyboard::new(NodeAddr(0));
+        keyboard.update(&mut nodes);
+        assert_eq!(nodes[8].state(), 0);
+
+        keyboard.push('é');
+        keyboard.update(&mut nodes);
+        assert_eq!(nodes[8].state(), 0, "non-ASCII keys are ignored");
+
+        keyboard.push('A');
+        keyboard.update(&mut nodes);
+        assert_eq!(read_bits(&nodes, NodeAddr(1), 7), b'A' as u32);
+        assert_eq!(nodes[8].state(), 1);
+
+        // Ready is cleared on the rising edge of acknowledge, keeping the code
+        nodes[0].set_state(1);
+        keyboard.update(&mut nodes);
+        assert_eq!(nodes[8].state(), 0);
+        assert_eq!(read_bits(&nodes, NodeAddr(1), 7), b'A' as u32);
+
+        // A key typed while acknowledge is still high stays ready
+        keyboard.push('b');
+        keyboard.update(&mut nodes);
+        assert_eq!(nodes[8].state(), 1);
+        nodes[0].set_state(0);
+        keyboard.update(&mut nodes);
+        assert_eq!(nodes[8].state(), 1);
+        nodes[0].set_state(1);
+        keyboard.update(&mut nodes);
+        assert_eq!(nodes[8].state(), 0);
+    }
+}
@@ -0,0 +1,194 @@
+//! Orthogonal (Manhattan) wire routing around device bounds.
+
+use crate::geom::Rect;
+use crate::sim::scene::{Edge, UNIT};
+
+use glam::{ivec2, vec2, IVec2, Vec2};
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+/// How far outside of the box around the two end-points the router is allowed to search, in grid cells.
+const SEARCH_MARGIN: i32 = 12;
+/// Extra cost of changing direction, so that paths with fewer corners are preferred.
+const TURN_COST: u32 = 4;
+
+const DIRS: [IVec2; 4] = [ivec2(1, 0), ivec2(0, 1), ivec2(-1, 0), ivec2(0, -1)];
+
+/// A pin a wire is routed from or to.
+#[derive(Clone, Copy)]
+pub struct Endpoint {
+    pub pos: Vec2,
+    pub edge: Edge,
+}
+
+/// Computes the anchors of a wire going from `start` to `end` that only has horizontal and vertical
+/// segments, and doesn't cross any of the `obstacles`.
+/// Returns `None` if no path could be found.
+pub fn route(start: Endpoint, end: Endpoint, obstacles: &[Rect]) -> Option<Vec<Vec2>> {
+    // Leave the pins perpendicular to the edge they're on
+    let start_stub = start.pos + start.edge.normal() * UNIT;
+    let end_stub = end.pos + end.edge.normal() * UNIT;
+
+    // The grid is aligned to the start stub, so the path can leave the start pin in a straight line.
+    let origin = start_stub;
+    let to_world = |p: IVec2| origin + p.as_vec2() * UNIT;
+    let to_grid = |p: Vec2| ((p - origin) / UNIT).round().as_ivec2();
+
+    let goal = to_grid(end_stub);
+    let min = IVec2::ZERO.min(goal) - IVec2::splat(SEARCH_MARGIN);
+    let max = IVec2::ZERO.max(goal) + IVec2::splat(SEARCH_MARGIN);
+
+    let blocked = |p: IVec2| {
+        if p == IVec2::ZERO || p == goal {
+            return false;
+        }
+        let p = to_world(p);
+        obstacles
+            .iter()
+            .any(|rect| rect.expand(UNIT * 0.25).contains(p))
+    };
+    let heuristic = |p: IVec2| (goal - p).abs().element_sum() as u32;
+
+    // A* over (cell, direction of travel)
+    type State = (IVec2, u8);
+    // (estimated total cost, cost so far, cell, direction of travel)
+    type Entry = Reverse<(u32, u32, [i32; 2], u8)>;
+    let mut open: BinaryHeap<Entry> = BinaryHeap::new();
+    let mut cost: HashMap<State, u32> = HashMap::new();
+    let mut came_from: HashMap<State, State> = HashMap::new();
+
+    let start_dir = DIRS
+        .iter()
+        .position(|d| d.as_vec2() == start.edge.normal())
+        .unwrap_or(0) as u8;
+    cost.insert((IVec2::ZERO, start_dir), 0);
+    open.push(Reverse((heuristic(IVec2::ZERO), 0, [0, 0], start_dir)));
+
+    let mut found = None;
+    while let Some(Reverse((_, g, [x, y], dir))) = open.pop() {
+        let p = ivec2(x, y);
+        if p == goal {
+            found = Some((p, dir));
+            break;
+        }
+        if cost.get(&(p, dir)).is_some_and(|c| *c < g) {
+            continue;
+        }
+        for (new_dir, d) in DIRS.iter().enumerate() {
+            let new_dir = new_dir as u8;
+            // never go straight back
+            if (new_dir + 2) % 4 == dir {
+                continue;
+            }
+            let next = p + *d;
+            if next.cmplt(min).any() || next.cmpgt(max).any() || blocked(next) {
+                continue;
+            }
+            let g = g + 1 + if new_dir != dir { TURN_COST } else { 0 };
+            let state = (next, new_dir);
+            if cost.get(&state).is_some_and(|c| *c <= g) {
+                continue;
+            }
+            cost.insert(state, g);
+            came_from.insert(state, (p, dir));
+            open.push(Reverse((g + heuristic(next), g, next.to_array(), new_dir)));
+        }
+    }
+
+    // Walk back from the goal, only keeping the cells where the path turns.
+    let mut state = found?;
+    let mut cells = vec![state.0];
+    while let Some(prev) = came_from.get(&state) {
+        if prev.1 != state.1 {
+            cells.push(prev.0);
+        }
+        state = *prev;
+    }
+    if cells.last() != Some(&IVec2::ZERO) {
+        cells.push(IVec2::ZERO);
+    }
+    cells.reverse();
+
+    let mut anchors: Vec<Vec2> = cells.into_iter().map(to_world).collect();
+
+    // The end stub might not be on the grid, so jog over to it.
+    let last = *anchors.last().unwrap();
+    if last != end_stub {
+        match end.edge {
+            Edge::Left | Edge::Right => anchors.push(vec2(last.x, end_stub.y)),
+            Edge::Top | Edge::Bottom => anchors.push(vec2(end_stub.x, last.y)),
+        }
+        anchors.push(end_stub);
+    }
+
+    // Remove anchors that are in the middle of a straight line.
+    let mut points = vec![start.pos];
+    points.extend(anchors);
+    points.push(end.pos);
+    points.dedup();
+    let mut idx = 1;
+    while idx + 1 < points.len() {
+        let (a, b, c) = (points[idx - 1], points[idx], points[idx + 1]);
+        if (a.x == b.x && b.x == c.x) || (a.y == b.y && b.y == c.y) {
+            points.remove(idx);
+        } else {
+            idx += 1;
+        }
+    }
+    Some(points[1..points.len() - 1].to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoints(end: Vec2) -> (Endpoint, Endpoint) {
+        let start = Endpoint {
+            pos: Vec2::ZERO,
+            edge: Edge::Right,
+        };
+        let end = Endpoint {
+            pos: end,
+            edge: Edge::Left,
+        };
+        (start, end)
+    }
+
+    /// The lines a wire with `anchors` goes through.
+    fn lines(start: Endpoint, end: Endpoint, anchors: &[Vec2]) -> Vec<(Vec2, Vec2)> {
+        let mut points = vec![start.pos];
+        points.extend(anchors);
+        points.push(end.pos);
+        points.windows(2).map(|w| (w[0], w[1])).collect()
+    }
+
+    #[test]
+    fn straight() {
+        let (start, end) = endpoints(vec2(UNIT * 5.0, 0.0));
+        assert_eq!(route(start, end, &[]), Some(vec![]));
+    }
+
+    #[test]
+    fn around_obstacle() {
+        let (start, end) = endpoints(vec2(UNIT * 8.0, 0.0));
+        let obstacle = Rect::from_center_size(vec2(UNIT * 4.0, 0.0), vec2(UNIT, UNIT * 3.0));
+        let anchors = route(start, end, &[obstacle]).unwrap();
+        assert!(!anchors.is_empty());
+        for (a, b) in lines(start, end, &anchors) {
+            assert!(a.x == b.x || a.y == b.y, "{a} to {b} isn't orthogonal");
+            assert!(
+                !obstacle.intersects_line(a, b),
+                "{a} to {b} crosses the obstacle"
+            );
+        }
+    }
+
+    #[test]
+    fn no_path() {
+        // The end is boxed in, so there's no way to reach it
+        let (start, end) = endpoints(vec2(UNIT * 5.0, 0.0));
+        let obstacle = Rect::from_center_size(end.pos, vec2(UNIT * 5.0, UNIT * 5.0));
+        assert_eq!(route(start, end, &[obstacle]), None);
+    }
+}
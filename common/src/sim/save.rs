@@ -1,8 +1,11 @@
 use crate::sim::{self, scene, NodeRegion, TruthTable, TruthTableId};
+#[cfg(feature = "ui")]
 use egui::Color32 as Color;
 use glam::Vec2;
 use serde::{Deserialize, Serialize};
 
+mod legacy;
+
 pub type SaveId = crate::Id;
 
 pub fn create_chip_from_scene(scene: &crate::sim::scene::Scene) -> ChipSave {
@@ -33,6 +36,8 @@ pub fn create_chip_from_scene(scene: &crate::sim::scene::Scene) -> ChipSave {
         l_nodes,
         r_nodes,
         inner_nodes,
+        natives: scene.sim.natives.clone(),
+        tests: vec![],
     }
 }
 
@@ -42,18 +47,21 @@ pub fn create_basic_chip(
     inputs: &[&str],
     outputs: &[&str],
     map: Box<[u64]>,
+    dont_care: Box<[u64]>,
 ) -> (TruthTable, ChipSave) {
     let table = sim::TruthTable {
         num_inputs: inputs.len() as u8,
         num_outputs: outputs.len() as u8,
         name: name.into(),
         map,
+        dont_care,
     };
     let chip = ChipSave {
         attrs: ChipAttrs {
             name: name.into(),
             category: "Basic".into(),
             logic: Logic::Combinational,
+            pin_layout: Default::default(),
         },
         region_size: (inputs.len() + outputs.len()) as u32,
         builtin: true,
@@ -88,6 +96,8 @@ pub fn create_basic_chip(
             })
             .collect(),
         inner_nodes: vec![],
+        natives: vec![],
+        tests: vec![],
     };
     (table, chip)
 }
@@ -115,53 +125,20 @@ impl StartingChip {
     }
 
     pub fn create(self, library: &mut Library) {
-        let table_id = library.allocate_table_empty();
-        let (table, chip) = match self {
-            Self::And => create_basic_chip(
-                table_id,
-                "And",
-                &["a", "b"],
-                &["out"],
-                Box::new([0, 0, 0, 1]),
-            ),
-            Self::Not => create_basic_chip(table_id, "Not", &["in"], &["out"], Box::new([1, 0])),
-            Self::Nand => create_basic_chip(
-                table_id,
-                "Nand",
-                &["a", "b"],
-                &["out"],
-                Box::new([1, 1, 1, 0]),
-            ),
-            Self::Or => create_basic_chip(
-                table_id,
-                "Or",
-                &["a", "b"],
-                &["out"],
-                Box::new([0, 1, 1, 1]),
-            ),
-            Self::Nor => create_basic_chip(
-                table_id,
-                "Nor",
-                &["a", "b"],
-                &["out"],
-                Box::new([1, 0, 0, 0]),
-            ),
-            Self::Xor => create_basic_chip(
-                table_id,
-                "Xor",
-                &["a", "b"],
-                &["out"],
-                Box::new([0, 1, 1, 0]),
-            ),
-            Self::HalfAdder => create_basic_chip(
-                table_id,
+        let (name, inputs, outputs, map): (_, &[&str], &[&str], Box<[u64]>) = match self {
+            Self::And => ("And", &["a", "b"], &["out"], Box::new([0, 0, 0, 1])),
+            Self::Not => ("Not", &["in"], &["out"], Box::new([1, 0])),
+            Self::Nand => ("Nand", &["a", "b"], &["out"], Box::new([1, 1, 1, 0])),
+            Self::Or => ("Or", &["a", "b"], &["out"], Box::new([0, 1, 1, 1])),
+            Self::Nor => ("Nor", &["a", "b"], &["out"], Box::new([1, 0, 0, 0])),
+            Self::Xor => ("Xor", &["a", "b"], &["out"], Box::new([0, 1, 1, 0])),
+            Self::HalfAdder => (
                 "HalfAdder",
                 &["a", "b"],
                 &["sum", "cout"],
                 Box::new([0b00, 0b01, 0b01, 0b10]),
             ),
-            Self::Adder => create_basic_chip(
-                table_id,
+            Self::Adder => (
                 "Adder",
                 &["a", "b", "cin"],
                 &["sum", "cout"],
@@ -169,8 +146,9 @@ impl StartingChip {
                 Box::new([0b00, 0b01, 0b01, 0b10, 0b01, 0b10, 0b10, 0b11]),
             ),
         };
-        library.add_chip(chip);
-        library.tables[table_id.0 as usize] = table;
+        library
+            .add_basic_chip(name, inputs, outputs, map, Box::new([]))
+            .expect("a new library has room for the starting chips");
     }
 }
 
@@ -192,6 +170,57 @@ impl Project {
             library,
         }
     }
+
+    /// Resets the state of devices that isn't kept when the project is loaded, like volatile RAM.
+    pub fn on_load(&mut self) {
+        let scene_natives = self
+            .scenes
+            .iter_mut()
+            .flat_map(|scene| &mut scene.sim.natives);
+        let chip_natives = self
+            .library
+            .chips
+            .iter_mut()
+            .flat_map(|chip| &mut chip.natives);
+        scene_natives
+            .chain(chip_natives)
+            .for_each(|native| native.on_load());
+    }
+}
+
+/// The bytes a saved project starts with, followed by the version of it's format. Projects saved
+/// before the format had a version start with the length of their name instead.
+const PROJECT_MAGIC: &[u8; 4] = b"MLSP";
+/// The version of the format projects are saved in. It's bumped whenever a saved type changes,
+/// along with a step in the `legacy` module that migrates projects from the previous version.
+pub const PROJECT_VERSION: u32 = 13;
+
+pub fn encode_project(project: &Project) -> Vec<u8> {
+    let mut bytes = PROJECT_MAGIC.to_vec();
+    bytes.extend(PROJECT_VERSION.to_le_bytes());
+    bincode::serialize_into(&mut bytes, project).unwrap();
+    bytes
+}
+
+/// Reads a project saved by [encode_project] in this or an older version of the format, or in
+/// the layout from before projects had a version.
+pub fn decode_project(bytes: &[u8]) -> Result<Project, String> {
+    let Some(bytes) = bytes.strip_prefix(PROJECT_MAGIC) else {
+        return legacy::decode(0, bytes).map_err(|err| format!("it isn't a project ({err})"));
+    };
+    let Some((version, bytes)) = bytes.split_first_chunk() else {
+        return Err(String::from("it ends before it's version"));
+    };
+    let project = match u32::from_le_bytes(*version) {
+        PROJECT_VERSION => bincode::deserialize(bytes),
+        version if version < PROJECT_VERSION => legacy::decode(version, bytes),
+        version => {
+            return Err(format!(
+                "it's saved in version {version} of the format, which is newer than this one ({PROJECT_VERSION})"
+            ))
+        }
+    };
+    project.map_err(|err| format!("it's corrupted ({err})"))
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -206,7 +235,7 @@ pub struct Library {
     pub chips: Vec<ChipSave>,
 }
 impl Library {
-    pub fn categories<'a>(&'a self) -> impl Iterator<Item = &'a str> + '_ {
+    pub fn categories<'a>(&'a self) -> impl Iterator<Item = &'a str> + 'a {
         let mut results: Vec<&'a str> = vec![];
         for chip in &self.chips {
             if !results.contains(&chip.attrs.category.as_str()) {
@@ -230,6 +259,29 @@ impl Library {
         self.chips.push(chip);
     }
 
+    /// Adds a truth table, and a chip that uses it.
+    /// Fails if the library has no room for another table.
+    pub fn add_basic_chip(
+        &mut self,
+        name: &str,
+        inputs: &[&str],
+        outputs: &[&str],
+        map: Box<[u64]>,
+        dont_care: Box<[u64]>,
+    ) -> Result<TruthTableId, String> {
+        if self.tables.len() > u8::MAX as usize {
+            return Err(format!(
+                "the library can't hold more than {} truth tables",
+                u8::MAX as usize + 1
+            ));
+        }
+        let table_id = self.allocate_table_empty();
+        let (table, chip) = create_basic_chip(table_id, name, inputs, outputs, map, dont_care);
+        self.tables[table_id.0 as usize] = table;
+        self.add_chip(chip);
+        Ok(table_id)
+    }
+
     pub fn allocate_table_empty(&mut self) -> TruthTableId {
         let id = TruthTableId(self.tables.len() as u8);
         self.tables.push(Default::default());
@@ -288,6 +340,8 @@ impl ItemColor {
     }
 
     pub const COUNT: u8 = 12;
+
+    #[cfg(feature = "ui")]
     pub fn as_color(self) -> Color {
         match self {
             Self::White => Color::WHITE,
@@ -311,6 +365,7 @@ pub struct ChipAttrs {
     pub name: String,
     pub category: String,
     pub logic: Logic,
+    pub pin_layout: scene::PinLayout,
 }
 impl Default for ChipAttrs {
     fn default() -> Self {
@@ -318,6 +373,7 @@ impl Default for ChipAttrs {
             name: String::from("New Chip"),
             category: String::from("Basic"),
             logic: Logic::Combinational,
+            pin_layout: Default::default(),
         }
     }
 }
@@ -331,9 +387,17 @@ pub struct ChipSave {
     pub l_nodes: Vec<(String, sim::NodeAddr, sim::Node)>,
     pub r_nodes: Vec<(String, sim::NodeAddr, sim::Node)>,
     pub inner_nodes: Vec<(sim::NodeAddr, sim::Node)>,
+    pub natives: Vec<sim::native::NativeDevice>,
+    /// The vectors the chip is tested with.
+    pub tests: Vec<sim::vector::TestVector>,
 }
 impl ChipSave {
-    pub fn preview(&self, pos: Vec2, rotation: scene::Rotation) -> scene::Chip {
+    pub fn preview(
+        &self,
+        pos: Vec2,
+        rotation: scene::Rotation,
+        mirror: scene::Mirror,
+    ) -> scene::Chip {
         fn io_ty(node: &sim::Node) -> IoType {
             match node.source().ty() {
                 sim::SourceTy::NONE => IoType::Input,
@@ -357,6 +421,7 @@ impl ChipSave {
             region: NodeRegion::default(),
             pos,
             rotation,
+            mirror,
             save: None,
             l_nodes,
             r_nodes,
@@ -364,3 +429,39 @@ impl ChipSave {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let project = Project::new(String::from("Test"), vec![StartingChip::And]);
+        let bytes = encode_project(&project);
+        assert!(bytes.starts_with(PROJECT_MAGIC));
+        let decoded = decode_project(&bytes).unwrap();
+        assert_eq!(decoded.name, "Test");
+        assert_eq!(decoded.library.chips[0].attrs.name, "And");
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let mut bytes = encode_project(&Project::default());
+        bytes[4..8].copy_from_slice(&(PROJECT_VERSION + 1).to_le_bytes());
+        let err = decode_project(&bytes).err().unwrap();
+        assert!(err.contains("newer"), "{err}");
+        assert!(decode_project(b"MLSP").is_err());
+        assert!(decode_project(&[1, 2, 3]).is_err());
+    }
+
+    /// Button keys were saved as `egui::Key`, which [scene::Key] has to be read the same as.
+    #[cfg(feature = "ui")]
+    #[test]
+    fn keys_are_saved_like_egui_keys() {
+        for key in egui::Key::ALL {
+            let ours = bincode::serialize(&scene::Key::from_egui(*key)).unwrap();
+            assert_eq!(ours, bincode::serialize(key).unwrap(), "{key:?}");
+            assert_eq!(scene::Key::from_egui(*key).to_egui(), Some(*key));
+        }
+    }
+}
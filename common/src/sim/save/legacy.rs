@@ -0,0 +1,1886 @@
+//! The layouts projects were saved in by older versions of the format, see
+//! [super::decode_project].
+//!
+//! Each version has a module with the types that changed in the version after it, and the rest
+//! are the same types as the next version's. Every type in a module is migrated into the next
+//! version's with [From], so a project is migrated one version at a time until it's current.
+//! The types can also be serialized, to write old projects in tests.
+
+use crate::geom::Transform;
+use crate::sim::debug::Breakpoint;
+use crate::sim::native::NativeDevice;
+use crate::sim::save::{IoType, Logic};
+use crate::sim::scene::{
+    BuiltinDeviceTy, ExternalNodes, Mirror, NodeIdent, PinPlacement, Rotation, SceneId, WireBundle,
+};
+use crate::sim::snapshot::NamedSnapshot;
+use crate::sim::vector::TestVector;
+use crate::sim::{Node, NodeAddr, NodeRegion};
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The types that are saved now, which the newest version below migrates into.
+mod current {
+    pub use crate::sim::save::{ChipAttrs, ChipSave, Library, Project};
+    pub use crate::sim::scene::{BuiltinData, BuiltinDevice, Chip, Device, PinLayout, Scene, Wire};
+    pub use crate::sim::{Sim, TruthTable};
+}
+
+/// Reads a project saved in an older `version` of the format, and migrates it.
+pub fn decode(version: u32, bytes: &[u8]) -> bincode::Result<current::Project> {
+    Ok(match version {
+        0 => v0::migrate(bincode::deserialize(bytes)?),
+        1 => v1::migrate(bincode::deserialize(bytes)?),
+        2 => v2::migrate(bincode::deserialize(bytes)?),
+        3 => v3::migrate(bincode::deserialize(bytes)?),
+        4 => v4::migrate(bincode::deserialize(bytes)?),
+        5 => v5::migrate(bincode::deserialize(bytes)?),
+        6 => v6::migrate(bincode::deserialize(bytes)?),
+        7 => v7::migrate(bincode::deserialize(bytes)?),
+        8 => v8::migrate(bincode::deserialize(bytes)?),
+        9 => v9::migrate(bincode::deserialize(bytes)?),
+        10 => v10::migrate(bincode::deserialize(bytes)?),
+        11 => v11::migrate(bincode::deserialize(bytes)?),
+        12 => v12::migrate(bincode::deserialize(bytes)?),
+        _ => unreachable!("version {version} isn't older than the current one"),
+    })
+}
+
+fn map_into<A: Into<B>, B>(list: Vec<A>) -> Vec<B> {
+    list.into_iter().map(Into::into).collect()
+}
+
+/// The layout from before there were versions, when pins were always on the sides of a chip and
+/// devices couldn't be mirrored.
+mod v0 {
+    use super::v1 as next;
+    pub use super::v1::*;
+    use super::*;
+
+    pub fn migrate(project: Project) -> current::Project {
+        next::migrate(project.into())
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Project {
+        pub name: String,
+        pub scenes: Vec<Scene>,
+        pub library: Library,
+    }
+    impl From<Project> for next::Project {
+        fn from(project: Project) -> Self {
+            Self {
+                name: project.name,
+                scenes: map_into(project.scenes),
+                library: project.library.into(),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Library {
+        pub tables: Vec<TruthTable>,
+        pub chips: Vec<ChipSave>,
+    }
+    impl From<Library> for next::Library {
+        fn from(library: Library) -> Self {
+            Self {
+                tables: library.tables,
+                chips: map_into(library.chips),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct ChipAttrs {
+        pub name: String,
+        pub category: String,
+        pub logic: Logic,
+    }
+    impl From<ChipAttrs> for next::ChipAttrs {
+        fn from(attrs: ChipAttrs) -> Self {
+            Self {
+                name: attrs.name,
+                category: attrs.category,
+                logic: attrs.logic,
+                pin_layout: Default::default(),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct ChipSave {
+        pub builtin: bool,
+        pub region_size: u32,
+        pub attrs: ChipAttrs,
+        pub scene: Option<Scene>,
+        pub l_nodes: Vec<(String, NodeAddr, Node)>,
+        pub r_nodes: Vec<(String, NodeAddr, Node)>,
+        pub inner_nodes: Vec<(NodeAddr, Node)>,
+    }
+    impl From<ChipSave> for next::ChipSave {
+        fn from(save: ChipSave) -> Self {
+            Self {
+                builtin: save.builtin,
+                region_size: save.region_size,
+                attrs: save.attrs.into(),
+                scene: save.scene.map(Into::into),
+                l_nodes: save.l_nodes,
+                r_nodes: save.r_nodes,
+                inner_nodes: save.inner_nodes,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Scene {
+        pub sim: Sim,
+        pub save_attrs: ChipAttrs,
+        pub transform: Transform,
+        pub l_nodes: ExternalNodes,
+        pub r_nodes: ExternalNodes,
+        pub devices: HashMap<SceneId, Device>,
+        pub wires: Vec<Wire>,
+        pub wire_bundles: Vec<WireBundle>,
+    }
+    impl From<Scene> for next::Scene {
+        fn from(scene: Scene) -> Self {
+            Self {
+                sim: scene.sim,
+                save_attrs: scene.save_attrs.into(),
+                transform: scene.transform,
+                l_nodes: scene.l_nodes,
+                r_nodes: scene.r_nodes,
+                devices: (scene.devices.into_iter())
+                    .map(|(id, device)| (id, device.into()))
+                    .collect(),
+                wires: scene.wires,
+                wire_bundles: scene.wire_bundles,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub enum Device {
+        Chip(Chip),
+        Builtin(BuiltinDevice),
+    }
+    impl From<Device> for next::Device {
+        fn from(device: Device) -> Self {
+            match device {
+                Device::Chip(chip) => Self::Chip(chip.into()),
+                Device::Builtin(device) => Self::Builtin(device.into()),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Chip {
+        pub attrs: ChipAttrs,
+        pub region: NodeRegion,
+        pub pos: Vec2,
+        pub rotation: Rotation,
+        pub save: Option<usize>,
+        pub l_nodes: Vec<(NodeAddr, String, IoType)>,
+        pub r_nodes: Vec<(NodeAddr, String, IoType)>,
+        pub inner_nodes: Vec<NodeAddr>,
+    }
+    impl From<Chip> for next::Chip {
+        fn from(chip: Chip) -> Self {
+            Self {
+                attrs: chip.attrs.into(),
+                region: chip.region,
+                pos: chip.pos,
+                rotation: chip.rotation,
+                mirror: Default::default(),
+                save: chip.save,
+                l_nodes: chip.l_nodes,
+                r_nodes: chip.r_nodes,
+                inner_nodes: chip.inner_nodes,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct BuiltinDevice {
+        pub ty: BuiltinDeviceTy,
+        pub region: NodeRegion,
+        pub pos: Vec2,
+        pub rotation: Rotation,
+        pub l_nodes: Vec<(NodeAddr, String, IoType)>,
+        pub r_nodes: Vec<(NodeAddr, String, IoType)>,
+    }
+    impl From<BuiltinDevice> for next::BuiltinDevice {
+        fn from(device: BuiltinDevice) -> Self {
+            Self {
+                ty: device.ty,
+                region: device.region,
+                pos: device.pos,
+                rotation: device.rotation,
+                mirror: Default::default(),
+                l_nodes: device.l_nodes,
+                r_nodes: device.r_nodes,
+            }
+        }
+    }
+}
+
+/// Wires were always drawn along their anchors, and never routed around devices.
+mod v1 {
+    use super::v2 as next;
+    pub use super::v2::*;
+    use super::*;
+
+    pub fn migrate(project: Project) -> current::Project {
+        next::migrate(project.into())
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Project {
+        pub name: String,
+        pub scenes: Vec<Scene>,
+        pub library: Library,
+    }
+    impl From<Project> for next::Project {
+        fn from(project: Project) -> Self {
+            Self {
+                name: project.name,
+                scenes: map_into(project.scenes),
+                library: project.library.into(),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Library {
+        pub tables: Vec<TruthTable>,
+        pub chips: Vec<ChipSave>,
+    }
+    impl From<Library> for next::Library {
+        fn from(library: Library) -> Self {
+            Self {
+                tables: library.tables,
+                chips: map_into(library.chips),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct ChipSave {
+        pub builtin: bool,
+        pub region_size: u32,
+        pub attrs: ChipAttrs,
+        pub scene: Option<Scene>,
+        pub l_nodes: Vec<(String, NodeAddr, Node)>,
+        pub r_nodes: Vec<(String, NodeAddr, Node)>,
+        pub inner_nodes: Vec<(NodeAddr, Node)>,
+    }
+    impl From<ChipSave> for next::ChipSave {
+        fn from(save: ChipSave) -> Self {
+            Self {
+                builtin: save.builtin,
+                region_size: save.region_size,
+                attrs: save.attrs,
+                scene: save.scene.map(Into::into),
+                l_nodes: save.l_nodes,
+                r_nodes: save.r_nodes,
+                inner_nodes: save.inner_nodes,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Scene {
+        pub sim: Sim,
+        pub save_attrs: ChipAttrs,
+        pub transform: Transform,
+        pub l_nodes: ExternalNodes,
+        pub r_nodes: ExternalNodes,
+        pub devices: HashMap<SceneId, Device>,
+        pub wires: Vec<Wire>,
+        pub wire_bundles: Vec<WireBundle>,
+    }
+    impl From<Scene> for next::Scene {
+        fn from(scene: Scene) -> Self {
+            Self {
+                sim: scene.sim,
+                save_attrs: scene.save_attrs,
+                transform: scene.transform,
+                l_nodes: scene.l_nodes,
+                r_nodes: scene.r_nodes,
+                devices: scene.devices,
+                wires: map_into(scene.wires),
+                wire_bundles: scene.wire_bundles,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Wire {
+        pub input: NodeIdent,
+        pub output: NodeIdent,
+        pub anchors: Vec<Vec2>,
+    }
+    impl From<Wire> for next::Wire {
+        fn from(wire: Wire) -> Self {
+            Self {
+                input: wire.input,
+                output: wire.output,
+                anchors: wire.anchors,
+                routed: false,
+            }
+        }
+    }
+}
+
+/// Wires couldn't branch off of other wires.
+mod v2 {
+    use super::v3 as next;
+    pub use super::v3::*;
+    use super::*;
+
+    pub fn migrate(project: Project) -> current::Project {
+        next::migrate(project.into())
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Project {
+        pub name: String,
+        pub scenes: Vec<Scene>,
+        pub library: Library,
+    }
+    impl From<Project> for next::Project {
+        fn from(project: Project) -> Self {
+            Self {
+                name: project.name,
+                scenes: map_into(project.scenes),
+                library: project.library.into(),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Library {
+        pub tables: Vec<TruthTable>,
+        pub chips: Vec<ChipSave>,
+    }
+    impl From<Library> for next::Library {
+        fn from(library: Library) -> Self {
+            Self {
+                tables: library.tables,
+                chips: map_into(library.chips),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct ChipSave {
+        pub builtin: bool,
+        pub region_size: u32,
+        pub attrs: ChipAttrs,
+        pub scene: Option<Scene>,
+        pub l_nodes: Vec<(String, NodeAddr, Node)>,
+        pub r_nodes: Vec<(String, NodeAddr, Node)>,
+        pub inner_nodes: Vec<(NodeAddr, Node)>,
+    }
+    impl From<ChipSave> for next::ChipSave {
+        fn from(save: ChipSave) -> Self {
+            Self {
+                builtin: save.builtin,
+                region_size: save.region_size,
+                attrs: save.attrs,
+                scene: save.scene.map(Into::into),
+                l_nodes: save.l_nodes,
+                r_nodes: save.r_nodes,
+                inner_nodes: save.inner_nodes,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Scene {
+        pub sim: Sim,
+        pub save_attrs: ChipAttrs,
+        pub transform: Transform,
+        pub l_nodes: ExternalNodes,
+        pub r_nodes: ExternalNodes,
+        pub devices: HashMap<SceneId, Device>,
+        pub wires: Vec<Wire>,
+        pub wire_bundles: Vec<WireBundle>,
+    }
+    impl From<Scene> for next::Scene {
+        fn from(scene: Scene) -> Self {
+            Self {
+                sim: scene.sim,
+                save_attrs: scene.save_attrs,
+                transform: scene.transform,
+                l_nodes: scene.l_nodes,
+                r_nodes: scene.r_nodes,
+                devices: scene.devices,
+                wires: map_into(scene.wires),
+                wire_bundles: scene.wire_bundles,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Wire {
+        pub input: NodeIdent,
+        pub output: NodeIdent,
+        pub anchors: Vec<Vec2>,
+        pub routed: bool,
+    }
+    impl From<Wire> for next::Wire {
+        fn from(wire: Wire) -> Self {
+            Self {
+                input: wire.input,
+                output: wire.output,
+                anchors: wire.anchors,
+                routed: wire.routed,
+                junction: None,
+            }
+        }
+    }
+}
+
+/// Builtin devices had no data of their own, since every device of a type was the same.
+mod v3 {
+    use super::v4 as next;
+    pub use super::v4::*;
+    use super::*;
+
+    pub fn migrate(project: Project) -> current::Project {
+        next::migrate(project.into())
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Project {
+        pub name: String,
+        pub scenes: Vec<Scene>,
+        pub library: Library,
+    }
+    impl From<Project> for next::Project {
+        fn from(project: Project) -> Self {
+            Self {
+                name: project.name,
+                scenes: map_into(project.scenes),
+                library: project.library.into(),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Library {
+        pub tables: Vec<TruthTable>,
+        pub chips: Vec<ChipSave>,
+    }
+    impl From<Library> for next::Library {
+        fn from(library: Library) -> Self {
+            Self {
+                tables: library.tables,
+                chips: map_into(library.chips),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct ChipSave {
+        pub builtin: bool,
+        pub region_size: u32,
+        pub attrs: ChipAttrs,
+        pub scene: Option<Scene>,
+        pub l_nodes: Vec<(String, NodeAddr, Node)>,
+        pub r_nodes: Vec<(String, NodeAddr, Node)>,
+        pub inner_nodes: Vec<(NodeAddr, Node)>,
+    }
+    impl From<ChipSave> for next::ChipSave {
+        fn from(save: ChipSave) -> Self {
+            Self {
+                builtin: save.builtin,
+                region_size: save.region_size,
+                attrs: save.attrs,
+                scene: save.scene.map(Into::into),
+                l_nodes: save.l_nodes,
+                r_nodes: save.r_nodes,
+                inner_nodes: save.inner_nodes,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Scene {
+        pub sim: Sim,
+        pub save_attrs: ChipAttrs,
+        pub transform: Transform,
+        pub l_nodes: ExternalNodes,
+        pub r_nodes: ExternalNodes,
+        pub devices: HashMap<SceneId, Device>,
+        pub wires: Vec<Wire>,
+        pub wire_bundles: Vec<WireBundle>,
+    }
+    impl From<Scene> for next::Scene {
+        fn from(scene: Scene) -> Self {
+            Self {
+                sim: scene.sim,
+                save_attrs: scene.save_attrs,
+                transform: scene.transform,
+                l_nodes: scene.l_nodes,
+                r_nodes: scene.r_nodes,
+                devices: (scene.devices.into_iter())
+                    .map(|(id, device)| (id, device.into()))
+                    .collect(),
+                wires: scene.wires,
+                wire_bundles: scene.wire_bundles,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub enum Device {
+        Chip(Chip),
+        Builtin(BuiltinDevice),
+    }
+    impl From<Device> for next::Device {
+        fn from(device: Device) -> Self {
+            match device {
+                Device::Chip(chip) => Self::Chip(chip),
+                Device::Builtin(device) => Self::Builtin(device.into()),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct BuiltinDevice {
+        pub ty: BuiltinDeviceTy,
+        pub region: NodeRegion,
+        pub pos: Vec2,
+        pub rotation: Rotation,
+        pub mirror: Mirror,
+        pub l_nodes: Vec<(NodeAddr, String, IoType)>,
+        pub r_nodes: Vec<(NodeAddr, String, IoType)>,
+    }
+    impl From<BuiltinDevice> for next::BuiltinDevice {
+        fn from(device: BuiltinDevice) -> Self {
+            Self {
+                ty: device.ty,
+                data: next::BuiltinData::None,
+                region: device.region,
+                pos: device.pos,
+                rotation: device.rotation,
+                mirror: device.mirror,
+                l_nodes: device.l_nodes,
+                r_nodes: device.r_nodes,
+            }
+        }
+    }
+}
+
+/// There were no native devices, like clocks, that the simulation updates itself.
+mod v4 {
+    use super::v5 as next;
+    pub use super::v5::*;
+    use super::*;
+
+    pub fn migrate(project: Project) -> current::Project {
+        next::migrate(project.into())
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Project {
+        pub name: String,
+        pub scenes: Vec<Scene>,
+        pub library: Library,
+    }
+    impl From<Project> for next::Project {
+        fn from(project: Project) -> Self {
+            Self {
+                name: project.name,
+                scenes: map_into(project.scenes),
+                library: project.library.into(),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Library {
+        pub tables: Vec<TruthTable>,
+        pub chips: Vec<ChipSave>,
+    }
+    impl From<Library> for next::Library {
+        fn from(library: Library) -> Self {
+            Self {
+                tables: library.tables,
+                chips: map_into(library.chips),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct ChipSave {
+        pub builtin: bool,
+        pub region_size: u32,
+        pub attrs: ChipAttrs,
+        pub scene: Option<Scene>,
+        pub l_nodes: Vec<(String, NodeAddr, Node)>,
+        pub r_nodes: Vec<(String, NodeAddr, Node)>,
+        pub inner_nodes: Vec<(NodeAddr, Node)>,
+    }
+    impl From<ChipSave> for next::ChipSave {
+        fn from(save: ChipSave) -> Self {
+            Self {
+                builtin: save.builtin,
+                region_size: save.region_size,
+                attrs: save.attrs,
+                scene: save.scene.map(Into::into),
+                l_nodes: save.l_nodes,
+                r_nodes: save.r_nodes,
+                inner_nodes: save.inner_nodes,
+                natives: vec![],
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Scene {
+        pub sim: Sim,
+        pub save_attrs: ChipAttrs,
+        pub transform: Transform,
+        pub l_nodes: ExternalNodes,
+        pub r_nodes: ExternalNodes,
+        pub devices: HashMap<SceneId, Device>,
+        pub wires: Vec<Wire>,
+        pub wire_bundles: Vec<WireBundle>,
+    }
+    impl From<Scene> for next::Scene {
+        fn from(scene: Scene) -> Self {
+            Self {
+                sim: scene.sim.into(),
+                save_attrs: scene.save_attrs,
+                transform: scene.transform,
+                l_nodes: scene.l_nodes,
+                r_nodes: scene.r_nodes,
+                devices: scene.devices,
+                wires: scene.wires,
+                wire_bundles: scene.wire_bundles,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Sim {
+        pub nodes: Vec<Node>,
+        pub next_region: u32,
+    }
+    impl From<Sim> for next::Sim {
+        fn from(sim: Sim) -> Self {
+            Self {
+                nodes: sim.nodes,
+                next_region: sim.next_region,
+                natives: vec![],
+                tick: 0,
+            }
+        }
+    }
+}
+
+/// Buttons had no key binding, so the data of builtin devices didn't have a variant for them.
+mod v5 {
+    use super::v6 as next;
+    pub use super::v6::*;
+    use super::*;
+
+    pub fn migrate(project: Project) -> current::Project {
+        next::migrate(project.into())
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Project {
+        pub name: String,
+        pub scenes: Vec<Scene>,
+        pub library: Library,
+    }
+    impl From<Project> for next::Project {
+        fn from(project: Project) -> Self {
+            Self {
+                name: project.name,
+                scenes: map_into(project.scenes),
+                library: project.library.into(),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Library {
+        pub tables: Vec<TruthTable>,
+        pub chips: Vec<ChipSave>,
+    }
+    impl From<Library> for next::Library {
+        fn from(library: Library) -> Self {
+            Self {
+                tables: library.tables,
+                chips: map_into(library.chips),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct ChipSave {
+        pub builtin: bool,
+        pub region_size: u32,
+        pub attrs: ChipAttrs,
+        pub scene: Option<Scene>,
+        pub l_nodes: Vec<(String, NodeAddr, Node)>,
+        pub r_nodes: Vec<(String, NodeAddr, Node)>,
+        pub inner_nodes: Vec<(NodeAddr, Node)>,
+        pub natives: Vec<NativeDevice>,
+    }
+    impl From<ChipSave> for next::ChipSave {
+        fn from(save: ChipSave) -> Self {
+            Self {
+                builtin: save.builtin,
+                region_size: save.region_size,
+                attrs: save.attrs,
+                scene: save.scene.map(Into::into),
+                l_nodes: save.l_nodes,
+                r_nodes: save.r_nodes,
+                inner_nodes: save.inner_nodes,
+                natives: save.natives,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Scene {
+        pub sim: Sim,
+        pub save_attrs: ChipAttrs,
+        pub transform: Transform,
+        pub l_nodes: ExternalNodes,
+        pub r_nodes: ExternalNodes,
+        pub devices: HashMap<SceneId, Device>,
+        pub wires: Vec<Wire>,
+        pub wire_bundles: Vec<WireBundle>,
+    }
+    impl From<Scene> for next::Scene {
+        fn from(scene: Scene) -> Self {
+            Self {
+                sim: scene.sim,
+                save_attrs: scene.save_attrs,
+                transform: scene.transform,
+                l_nodes: scene.l_nodes,
+                r_nodes: scene.r_nodes,
+                devices: (scene.devices.into_iter())
+                    .map(|(id, device)| (id, device.into()))
+                    .collect(),
+                wires: scene.wires,
+                wire_bundles: scene.wire_bundles,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub enum Device {
+        Chip(Chip),
+        Builtin(BuiltinDevice),
+    }
+    impl From<Device> for next::Device {
+        fn from(device: Device) -> Self {
+            match device {
+                Device::Chip(chip) => Self::Chip(chip),
+                Device::Builtin(device) => Self::Builtin(device.into()),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub enum BuiltinData {
+        None,
+        Tunnel(String),
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct BuiltinDevice {
+        pub ty: BuiltinDeviceTy,
+        pub data: BuiltinData,
+        pub region: NodeRegion,
+        pub pos: Vec2,
+        pub rotation: Rotation,
+        pub mirror: Mirror,
+        pub l_nodes: Vec<(NodeAddr, String, IoType)>,
+        pub r_nodes: Vec<(NodeAddr, String, IoType)>,
+    }
+    impl From<BuiltinDevice> for next::BuiltinDevice {
+        fn from(device: BuiltinDevice) -> Self {
+            Self {
+                ty: device.ty,
+                data: match device.data {
+                    BuiltinData::None => device.ty.default_data(),
+                    BuiltinData::Tunnel(name) => next::BuiltinData::Tunnel(name),
+                },
+                region: device.region,
+                pos: device.pos,
+                rotation: device.rotation,
+                mirror: device.mirror,
+                l_nodes: device.l_nodes,
+                r_nodes: device.r_nodes,
+            }
+        }
+    }
+}
+
+/// Nothing was recorded for the waveform viewer.
+mod v6 {
+    use super::v7 as next;
+    pub use super::v7::*;
+    use super::*;
+
+    pub fn migrate(project: Project) -> current::Project {
+        next::migrate(project.into())
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Project {
+        pub name: String,
+        pub scenes: Vec<Scene>,
+        pub library: Library,
+    }
+    impl From<Project> for next::Project {
+        fn from(project: Project) -> Self {
+            Self {
+                name: project.name,
+                scenes: map_into(project.scenes),
+                library: project.library.into(),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Library {
+        pub tables: Vec<TruthTable>,
+        pub chips: Vec<ChipSave>,
+    }
+    impl From<Library> for next::Library {
+        fn from(library: Library) -> Self {
+            Self {
+                tables: library.tables,
+                chips: map_into(library.chips),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct ChipSave {
+        pub builtin: bool,
+        pub region_size: u32,
+        pub attrs: ChipAttrs,
+        pub scene: Option<Scene>,
+        pub l_nodes: Vec<(String, NodeAddr, Node)>,
+        pub r_nodes: Vec<(String, NodeAddr, Node)>,
+        pub inner_nodes: Vec<(NodeAddr, Node)>,
+        pub natives: Vec<NativeDevice>,
+    }
+    impl From<ChipSave> for next::ChipSave {
+        fn from(save: ChipSave) -> Self {
+            Self {
+                builtin: save.builtin,
+                region_size: save.region_size,
+                attrs: save.attrs,
+                scene: save.scene.map(Into::into),
+                l_nodes: save.l_nodes,
+                r_nodes: save.r_nodes,
+                inner_nodes: save.inner_nodes,
+                natives: save.natives,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Scene {
+        pub sim: Sim,
+        pub save_attrs: ChipAttrs,
+        pub transform: Transform,
+        pub l_nodes: ExternalNodes,
+        pub r_nodes: ExternalNodes,
+        pub devices: HashMap<SceneId, Device>,
+        pub wires: Vec<Wire>,
+        pub wire_bundles: Vec<WireBundle>,
+    }
+    impl From<Scene> for next::Scene {
+        fn from(scene: Scene) -> Self {
+            Self {
+                sim: scene.sim.into(),
+                save_attrs: scene.save_attrs,
+                transform: scene.transform,
+                l_nodes: scene.l_nodes,
+                r_nodes: scene.r_nodes,
+                devices: scene.devices,
+                wires: scene.wires,
+                wire_bundles: scene.wire_bundles,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Sim {
+        pub nodes: Vec<Node>,
+        pub next_region: u32,
+        pub natives: Vec<NativeDevice>,
+        pub tick: u64,
+    }
+    impl From<Sim> for next::Sim {
+        fn from(sim: Sim) -> Self {
+            Self {
+                nodes: sim.nodes,
+                next_region: sim.next_region,
+                natives: sim.natives,
+                tick: sim.tick,
+                recorder: Default::default(),
+            }
+        }
+    }
+}
+
+/// Scenes couldn't be paused, and had no snapshots.
+mod v7 {
+    use super::v8 as next;
+    pub use super::v8::*;
+    use super::*;
+
+    pub fn migrate(project: Project) -> current::Project {
+        next::migrate(project.into())
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Project {
+        pub name: String,
+        pub scenes: Vec<Scene>,
+        pub library: Library,
+    }
+    impl From<Project> for next::Project {
+        fn from(project: Project) -> Self {
+            Self {
+                name: project.name,
+                scenes: map_into(project.scenes),
+                library: project.library.into(),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Library {
+        pub tables: Vec<TruthTable>,
+        pub chips: Vec<ChipSave>,
+    }
+    impl From<Library> for next::Library {
+        fn from(library: Library) -> Self {
+            Self {
+                tables: library.tables,
+                chips: map_into(library.chips),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct ChipSave {
+        pub builtin: bool,
+        pub region_size: u32,
+        pub attrs: ChipAttrs,
+        pub scene: Option<Scene>,
+        pub l_nodes: Vec<(String, NodeAddr, Node)>,
+        pub r_nodes: Vec<(String, NodeAddr, Node)>,
+        pub inner_nodes: Vec<(NodeAddr, Node)>,
+        pub natives: Vec<NativeDevice>,
+    }
+    impl From<ChipSave> for next::ChipSave {
+        fn from(save: ChipSave) -> Self {
+            Self {
+                builtin: save.builtin,
+                region_size: save.region_size,
+                attrs: save.attrs,
+                scene: save.scene.map(Into::into),
+                l_nodes: save.l_nodes,
+                r_nodes: save.r_nodes,
+                inner_nodes: save.inner_nodes,
+                natives: save.natives,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Scene {
+        pub sim: Sim,
+        pub save_attrs: ChipAttrs,
+        pub transform: Transform,
+        pub l_nodes: ExternalNodes,
+        pub r_nodes: ExternalNodes,
+        pub devices: HashMap<SceneId, Device>,
+        pub wires: Vec<Wire>,
+        pub wire_bundles: Vec<WireBundle>,
+    }
+    impl From<Scene> for next::Scene {
+        fn from(scene: Scene) -> Self {
+            Self {
+                sim: scene.sim,
+                save_attrs: scene.save_attrs,
+                transform: scene.transform,
+                l_nodes: scene.l_nodes,
+                r_nodes: scene.r_nodes,
+                devices: scene.devices,
+                wires: scene.wires,
+                wire_bundles: scene.wire_bundles,
+                paused: false,
+                snapshots: vec![],
+            }
+        }
+    }
+}
+
+/// Scenes had no breakpoints.
+mod v8 {
+    use super::v9 as next;
+    pub use super::v9::*;
+    use super::*;
+
+    pub fn migrate(project: Project) -> current::Project {
+        next::migrate(project.into())
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Project {
+        pub name: String,
+        pub scenes: Vec<Scene>,
+        pub library: Library,
+    }
+    impl From<Project> for next::Project {
+        fn from(project: Project) -> Self {
+            Self {
+                name: project.name,
+                scenes: map_into(project.scenes),
+                library: project.library.into(),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Library {
+        pub tables: Vec<TruthTable>,
+        pub chips: Vec<ChipSave>,
+    }
+    impl From<Library> for next::Library {
+        fn from(library: Library) -> Self {
+            Self {
+                tables: library.tables,
+                chips: map_into(library.chips),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct ChipSave {
+        pub builtin: bool,
+        pub region_size: u32,
+        pub attrs: ChipAttrs,
+        pub scene: Option<Scene>,
+        pub l_nodes: Vec<(String, NodeAddr, Node)>,
+        pub r_nodes: Vec<(String, NodeAddr, Node)>,
+        pub inner_nodes: Vec<(NodeAddr, Node)>,
+        pub natives: Vec<NativeDevice>,
+    }
+    impl From<ChipSave> for next::ChipSave {
+        fn from(save: ChipSave) -> Self {
+            Self {
+                builtin: save.builtin,
+                region_size: save.region_size,
+                attrs: save.attrs,
+                scene: save.scene.map(Into::into),
+                l_nodes: save.l_nodes,
+                r_nodes: save.r_nodes,
+                inner_nodes: save.inner_nodes,
+                natives: save.natives,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Scene {
+        pub sim: Sim,
+        pub save_attrs: ChipAttrs,
+        pub transform: Transform,
+        pub l_nodes: ExternalNodes,
+        pub r_nodes: ExternalNodes,
+        pub devices: HashMap<SceneId, Device>,
+        pub wires: Vec<Wire>,
+        pub wire_bundles: Vec<WireBundle>,
+        pub paused: bool,
+        pub snapshots: Vec<NamedSnapshot>,
+    }
+    impl From<Scene> for next::Scene {
+        fn from(scene: Scene) -> Self {
+            Self {
+                sim: scene.sim,
+                save_attrs: scene.save_attrs,
+                transform: scene.transform,
+                l_nodes: scene.l_nodes,
+                r_nodes: scene.r_nodes,
+                devices: scene.devices,
+                wires: scene.wires,
+                wire_bundles: scene.wire_bundles,
+                paused: scene.paused,
+                snapshots: scene.snapshots,
+                breakpoints: vec![],
+            }
+        }
+    }
+}
+
+/// Chips had no test vectors.
+mod v9 {
+    use super::v10 as next;
+    pub use super::v10::*;
+    use super::*;
+
+    pub fn migrate(project: Project) -> current::Project {
+        next::migrate(project.into())
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Project {
+        pub name: String,
+        pub scenes: Vec<Scene>,
+        pub library: Library,
+    }
+    impl From<Project> for next::Project {
+        fn from(project: Project) -> Self {
+            Self {
+                name: project.name,
+                scenes: project.scenes,
+                library: project.library.into(),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Library {
+        pub tables: Vec<TruthTable>,
+        pub chips: Vec<ChipSave>,
+    }
+    impl From<Library> for next::Library {
+        fn from(library: Library) -> Self {
+            Self {
+                tables: library.tables,
+                chips: map_into(library.chips),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct ChipSave {
+        pub builtin: bool,
+        pub region_size: u32,
+        pub attrs: ChipAttrs,
+        pub scene: Option<Scene>,
+        pub l_nodes: Vec<(String, NodeAddr, Node)>,
+        pub r_nodes: Vec<(String, NodeAddr, Node)>,
+        pub inner_nodes: Vec<(NodeAddr, Node)>,
+        pub natives: Vec<NativeDevice>,
+    }
+    impl From<ChipSave> for next::ChipSave {
+        fn from(save: ChipSave) -> Self {
+            Self {
+                builtin: save.builtin,
+                region_size: save.region_size,
+                attrs: save.attrs,
+                scene: save.scene,
+                l_nodes: save.l_nodes,
+                r_nodes: save.r_nodes,
+                inner_nodes: save.inner_nodes,
+                natives: save.natives,
+                tests: vec![],
+            }
+        }
+    }
+}
+
+/// Pin placements were stored by the position of the pin on it's side, instead of by it's name.
+mod v10 {
+    use super::v11 as next;
+    pub use super::v11::*;
+    use super::*;
+
+    pub fn migrate(project: Project) -> current::Project {
+        next::migrate(project.into())
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Project {
+        pub name: String,
+        pub scenes: Vec<Scene>,
+        pub library: Library,
+    }
+    impl From<Project> for next::Project {
+        fn from(project: Project) -> Self {
+            Self {
+                name: project.name,
+                scenes: map_into(project.scenes),
+                library: project.library.into(),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Library {
+        pub tables: Vec<TruthTable>,
+        pub chips: Vec<ChipSave>,
+    }
+    impl From<Library> for next::Library {
+        fn from(library: Library) -> Self {
+            Self {
+                tables: library.tables,
+                chips: map_into(library.chips),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct ChipAttrs {
+        pub name: String,
+        pub category: String,
+        pub logic: Logic,
+        pub pin_layout: PinLayout,
+    }
+    impl ChipAttrs {
+        /// Migrates the attributes of a chip with the left and right pins `names`.
+        fn migrate(self, names: [Vec<String>; 2]) -> next::ChipAttrs {
+            next::ChipAttrs {
+                name: self.name,
+                category: self.category,
+                logic: self.logic,
+                pin_layout: self.pin_layout.migrate(names),
+            }
+        }
+    }
+
+    #[derive(Default, Serialize, Deserialize)]
+    pub struct PinLayout {
+        pub l_nodes: Vec<PinPlacement>,
+        pub r_nodes: Vec<PinPlacement>,
+    }
+    impl PinLayout {
+        /// Names the placements after the pins at their positions, dropping any without a pin.
+        fn migrate(self, [l_names, r_names]: [Vec<String>; 2]) -> next::PinLayout {
+            let named =
+                |list: Vec<PinPlacement>, names: Vec<String>| names.into_iter().zip(list).collect();
+            next::PinLayout {
+                l_nodes: named(self.l_nodes, l_names),
+                r_nodes: named(self.r_nodes, r_names),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct ChipSave {
+        pub builtin: bool,
+        pub region_size: u32,
+        pub attrs: ChipAttrs,
+        pub scene: Option<Scene>,
+        pub l_nodes: Vec<(String, NodeAddr, Node)>,
+        pub r_nodes: Vec<(String, NodeAddr, Node)>,
+        pub inner_nodes: Vec<(NodeAddr, Node)>,
+        pub natives: Vec<NativeDevice>,
+        pub tests: Vec<TestVector>,
+    }
+    impl From<ChipSave> for next::ChipSave {
+        fn from(save: ChipSave) -> Self {
+            let names = |nodes: &[(String, NodeAddr, Node)]| {
+                nodes.iter().map(|(name, ..)| name.clone()).collect()
+            };
+            Self {
+                builtin: save.builtin,
+                region_size: save.region_size,
+                attrs: (save.attrs).migrate([names(&save.l_nodes), names(&save.r_nodes)]),
+                scene: save.scene.map(Into::into),
+                l_nodes: save.l_nodes,
+                r_nodes: save.r_nodes,
+                inner_nodes: save.inner_nodes,
+                natives: save.natives,
+                tests: save.tests,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Scene {
+        pub sim: Sim,
+        pub save_attrs: ChipAttrs,
+        pub transform: Transform,
+        pub l_nodes: ExternalNodes,
+        pub r_nodes: ExternalNodes,
+        pub devices: HashMap<SceneId, Device>,
+        pub wires: Vec<Wire>,
+        pub wire_bundles: Vec<WireBundle>,
+        pub paused: bool,
+        pub snapshots: Vec<NamedSnapshot>,
+        pub breakpoints: Vec<Breakpoint>,
+    }
+    impl From<Scene> for next::Scene {
+        fn from(scene: Scene) -> Self {
+            let names = |nodes: &ExternalNodes| {
+                (nodes.states.iter())
+                    .map(|(_, name)| name.clone())
+                    .collect()
+            };
+            let save_attrs =
+                (scene.save_attrs).migrate([names(&scene.l_nodes), names(&scene.r_nodes)]);
+            Self {
+                sim: scene.sim,
+                save_attrs,
+                transform: scene.transform,
+                l_nodes: scene.l_nodes,
+                r_nodes: scene.r_nodes,
+                devices: (scene.devices.into_iter())
+                    .map(|(id, device)| (id, device.into()))
+                    .collect(),
+                wires: scene.wires,
+                wire_bundles: scene.wire_bundles,
+                paused: scene.paused,
+                snapshots: scene.snapshots,
+                breakpoints: scene.breakpoints,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub enum Device {
+        Chip(Chip),
+        Builtin(BuiltinDevice),
+    }
+    impl From<Device> for next::Device {
+        fn from(device: Device) -> Self {
+            match device {
+                Device::Chip(chip) => Self::Chip(chip.into()),
+                Device::Builtin(device) => Self::Builtin(device),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Chip {
+        pub attrs: ChipAttrs,
+        pub region: NodeRegion,
+        pub pos: Vec2,
+        pub rotation: Rotation,
+        pub mirror: Mirror,
+        pub save: Option<usize>,
+        pub l_nodes: Vec<(NodeAddr, String, IoType)>,
+        pub r_nodes: Vec<(NodeAddr, String, IoType)>,
+        pub inner_nodes: Vec<NodeAddr>,
+    }
+    impl From<Chip> for next::Chip {
+        fn from(chip: Chip) -> Self {
+            let names = |nodes: &[(NodeAddr, String, IoType)]| {
+                nodes.iter().map(|(_, name, _)| name.clone()).collect()
+            };
+            Self {
+                attrs: (chip.attrs).migrate([names(&chip.l_nodes), names(&chip.r_nodes)]),
+                region: chip.region,
+                pos: chip.pos,
+                rotation: chip.rotation,
+                mirror: chip.mirror,
+                save: chip.save,
+                l_nodes: chip.l_nodes,
+                r_nodes: chip.r_nodes,
+                inner_nodes: chip.inner_nodes,
+            }
+        }
+    }
+}
+
+/// Branches stored the point they started at, instead of where along their parent wire it is.
+mod v11 {
+    use super::v12 as next;
+    pub use super::v12::*;
+    use super::*;
+    use crate::sim::scene::{fraction_along, Junction, UNIT};
+
+    pub fn migrate(project: Project) -> current::Project {
+        next::migrate(project.into())
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Project {
+        pub name: String,
+        pub scenes: Vec<Scene>,
+        pub library: Library,
+    }
+    impl From<Project> for next::Project {
+        fn from(project: Project) -> Self {
+            Self {
+                name: project.name,
+                scenes: map_into(project.scenes),
+                library: project.library.into(),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Library {
+        pub tables: Vec<TruthTable>,
+        pub chips: Vec<ChipSave>,
+    }
+    impl From<Library> for next::Library {
+        fn from(library: Library) -> Self {
+            Self {
+                tables: library.tables,
+                chips: map_into(library.chips),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct ChipSave {
+        pub builtin: bool,
+        pub region_size: u32,
+        pub attrs: ChipAttrs,
+        pub scene: Option<Scene>,
+        pub l_nodes: Vec<(String, NodeAddr, Node)>,
+        pub r_nodes: Vec<(String, NodeAddr, Node)>,
+        pub inner_nodes: Vec<(NodeAddr, Node)>,
+        pub natives: Vec<NativeDevice>,
+        pub tests: Vec<TestVector>,
+    }
+    impl From<ChipSave> for next::ChipSave {
+        fn from(save: ChipSave) -> Self {
+            Self {
+                builtin: save.builtin,
+                region_size: save.region_size,
+                attrs: save.attrs,
+                scene: save.scene.map(Into::into),
+                l_nodes: save.l_nodes,
+                r_nodes: save.r_nodes,
+                inner_nodes: save.inner_nodes,
+                natives: save.natives,
+                tests: save.tests,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Scene {
+        pub sim: Sim,
+        pub save_attrs: ChipAttrs,
+        pub transform: Transform,
+        pub l_nodes: ExternalNodes,
+        pub r_nodes: ExternalNodes,
+        pub devices: HashMap<SceneId, Device>,
+        pub wires: Vec<Wire>,
+        pub wire_bundles: Vec<WireBundle>,
+        pub paused: bool,
+        pub snapshots: Vec<NamedSnapshot>,
+        pub breakpoints: Vec<Breakpoint>,
+    }
+    impl From<Scene> for next::Scene {
+        fn from(scene: Scene) -> Self {
+            let starts: Vec<_> = scene.wires.iter().map(|wire| wire.junction).collect();
+            let mut new = Self {
+                sim: scene.sim,
+                save_attrs: scene.save_attrs,
+                transform: scene.transform,
+                l_nodes: scene.l_nodes,
+                r_nodes: scene.r_nodes,
+                devices: scene.devices,
+                wires: map_into(scene.wires),
+                wire_bundles: scene.wire_bundles,
+                paused: scene.paused,
+                timeline: Default::default(),
+                snapshots: scene.snapshots,
+                breakpoints: scene.breakpoints,
+                hit_breakpoint: None,
+            };
+
+            // The points the wires went through, with branches starting at their old point
+            let points: Vec<Option<Vec<Vec2>>> = (new.wires.iter().zip(&starts))
+                .map(|(wire, start)| {
+                    let start = match start {
+                        Some(start) => *start,
+                        None => new.node_info(wire.input)?.pos,
+                    };
+                    let mut points = vec![start];
+                    points.extend(&wire.anchors);
+                    points.push(new.node_info(wire.output)?.pos);
+                    Some(points)
+                })
+                .collect();
+            for (idx, start) in starts.iter().enumerate() {
+                let Some(start) = *start else {
+                    continue;
+                };
+                new.wires[idx].junction = junction_near(&new.wires, &points, idx, start);
+            }
+            new
+        }
+    }
+
+    /// The junction on the closest wire to `pos` that's driven by the same node as the wire at
+    /// `idx`, if `pos` is on it. Wires that were moved after the branch was made may not be.
+    fn junction_near(
+        wires: &[next::Wire],
+        points: &[Option<Vec<Vec2>>],
+        idx: usize,
+        pos: Vec2,
+    ) -> Option<Junction> {
+        let mut closest: Option<(f32, Junction)> = None;
+        for (parent, points) in points.iter().enumerate() {
+            let Some(points) = points else {
+                continue;
+            };
+            if parent == idx || wires[parent].input != wires[idx].input {
+                continue;
+            }
+            for (segment, line) in points.windows(2).enumerate() {
+                let dir = line[1] - line[0];
+                let t = match dir.length_squared() > 0.0 {
+                    true => ((pos - line[0]).dot(dir) / dir.length_squared()).clamp(0.0, 1.0),
+                    false => 0.0,
+                };
+                let on_line = line[0] + dir * t;
+                let dist = on_line.distance(pos);
+                if dist < UNIT * 0.5 && closest.is_none_or(|(best, _)| dist < best) {
+                    let junction = Junction {
+                        parent: wires[parent].output,
+                        along: fraction_along(points, segment, on_line),
+                    };
+                    closest = Some((dist, junction));
+                }
+            }
+        }
+        closest.map(|(_, junction)| junction)
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Wire {
+        pub input: NodeIdent,
+        pub output: NodeIdent,
+        pub anchors: Vec<Vec2>,
+        pub routed: bool,
+        pub junction: Option<Vec2>,
+    }
+    impl From<Wire> for next::Wire {
+        fn from(wire: Wire) -> Self {
+            Self {
+                input: wire.input,
+                output: wire.output,
+                anchors: wire.anchors,
+                routed: wire.routed,
+                junction: None,
+            }
+        }
+    }
+}
+
+/// Truth tables had no outputs that could have either value.
+mod v12 {
+    use super::current as next;
+    pub use super::current::*;
+    use super::*;
+
+    pub fn migrate(project: Project) -> current::Project {
+        project.into()
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Project {
+        pub name: String,
+        pub scenes: Vec<Scene>,
+        pub library: Library,
+    }
+    impl From<Project> for next::Project {
+        fn from(project: Project) -> Self {
+            Self {
+                name: project.name,
+                scenes: project.scenes,
+                library: project.library.into(),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Library {
+        pub tables: Vec<TruthTable>,
+        pub chips: Vec<ChipSave>,
+    }
+    impl From<Library> for next::Library {
+        fn from(library: Library) -> Self {
+            Self {
+                tables: map_into(library.tables),
+                chips: library.chips,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct TruthTable {
+        pub num_inputs: u8,
+        pub num_outputs: u8,
+        pub name: String,
+        pub map: Box<[u64]>,
+    }
+    impl From<TruthTable> for next::TruthTable {
+        fn from(table: TruthTable) -> Self {
+            Self {
+                num_inputs: table.num_inputs,
+                num_outputs: table.num_outputs,
+                name: table.name,
+                map: table.map,
+                dont_care: Box::new([]),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::save::decode_project;
+    use crate::sim::scene::{Edge, NodeIdent, Side};
+    use crate::Id;
+
+    /// Writes a project with the header of an older `version` of the format.
+    fn encode<T: Serialize>(version: u32, project: &T) -> Vec<u8> {
+        let mut bytes = b"MLSP".to_vec();
+        bytes.extend(version.to_le_bytes());
+        bincode::serialize_into(&mut bytes, project).unwrap();
+        bytes
+    }
+
+    fn v0_attrs(name: &str) -> v0::ChipAttrs {
+        v0::ChipAttrs {
+            name: String::from(name),
+            category: String::from("Basic"),
+            logic: Logic::Combinational,
+        }
+    }
+
+    fn v0_scene() -> v0::Scene {
+        let button = v0::BuiltinDevice {
+            ty: BuiltinDeviceTy::Button,
+            region: Default::default(),
+            pos: Vec2::new(1.0, 2.0),
+            rotation: Default::default(),
+            l_nodes: vec![],
+            r_nodes: vec![],
+        };
+        v0::Scene {
+            sim: v0::Sim {
+                nodes: vec![Default::default(); 3],
+                next_region: 3,
+            },
+            save_attrs: v0_attrs("Main"),
+            transform: Default::default(),
+            l_nodes: Default::default(),
+            r_nodes: Default::default(),
+            devices: [(Id(1), v0::Device::Builtin(button))].into(),
+            wires: vec![v0::Wire {
+                input: NodeIdent::DeviceR(Id(1), 0),
+                output: NodeIdent::RExternal(0),
+                anchors: vec![Vec2::new(5.0, 2.0)],
+            }],
+            wire_bundles: vec![],
+        }
+    }
+
+    #[test]
+    fn migrates_unversioned_projects() {
+        let project = v0::Project {
+            name: String::from("Old"),
+            scenes: vec![v0_scene()],
+            library: v0::Library {
+                tables: vec![],
+                chips: vec![v0::ChipSave {
+                    builtin: false,
+                    region_size: 1,
+                    attrs: v0_attrs("Inner"),
+                    scene: Some(v0_scene()),
+                    l_nodes: vec![],
+                    r_nodes: vec![],
+                    inner_nodes: vec![],
+                }],
+            },
+        };
+        let project = decode_project(&bincode::serialize(&project).unwrap()).unwrap();
+        assert_eq!(project.name, "Old");
+        let chip = &project.library.chips[0];
+        assert_eq!(chip.attrs.name, "Inner");
+        assert!(chip.tests.is_empty() && chip.natives.is_empty());
+
+        for scene in project.scenes.iter().chain(chip.scene.as_ref()) {
+            assert_eq!(scene.save_attrs.name, "Main");
+            assert_eq!((scene.sim.nodes.len(), scene.sim.next_region), (3, 3));
+            let Some(current::Device::Builtin(button)) = scene.devices.get(&Id(1)) else {
+                panic!("the button wasn't migrated");
+            };
+            assert_eq!(button.pos, Vec2::new(1.0, 2.0));
+            assert!(matches!(
+                button.data,
+                current::BuiltinData::Button { key: None }
+            ));
+            assert_eq!(scene.wires[0].output, NodeIdent::RExternal(0));
+            assert_eq!(scene.wires[0].anchors, [Vec2::new(5.0, 2.0)]);
+            assert!(!scene.wires[0].routed && scene.wires[0].junction.is_none());
+        }
+    }
+
+    #[test]
+    fn moves_tunnels_past_buttons() {
+        let tunnel = v5::BuiltinDevice {
+            ty: BuiltinDeviceTy::Tunnel,
+            data: v5::BuiltinData::Tunnel(String::from("clk")),
+            region: Default::default(),
+            pos: Vec2::ZERO,
+            rotation: Default::default(),
+            mirror: Default::default(),
+            l_nodes: vec![],
+            r_nodes: vec![],
+        };
+        let scene = v5::Scene {
+            sim: v5::Sim {
+                nodes: vec![Default::default()],
+                next_region: 1,
+                natives: vec![],
+                tick: 0,
+            },
+            save_attrs: v5::ChipAttrs {
+                name: String::from("Main"),
+                category: String::from("Basic"),
+                logic: Logic::Combinational,
+                pin_layout: Default::default(),
+            },
+            transform: Default::default(),
+            l_nodes: Default::default(),
+            r_nodes: Default::default(),
+            devices: [(Id(1), v5::Device::Builtin(tunnel))].into(),
+            wires: vec![],
+            wire_bundles: vec![],
+        };
+        let project = v5::Project {
+            name: String::from("Tunnels"),
+            scenes: vec![scene],
+            library: v5::Library {
+                tables: vec![],
+                chips: vec![],
+            },
+        };
+        let project = decode_project(&encode(5, &project)).unwrap();
+        let Some(current::Device::Builtin(tunnel)) = project.scenes[0].devices.get(&Id(1)) else {
+            panic!("the tunnel wasn't migrated");
+        };
+        assert!(matches!(&tunnel.data, current::BuiltinData::Tunnel(name) if name == "clk"));
+    }
+
+    #[test]
+    fn names_pin_placements() {
+        let top = |order| PinPlacement {
+            edge: Edge::Top,
+            order,
+        };
+        let pin = |name: &str| (String::from(name), NodeAddr(0), Node::default());
+        let save = v10::ChipSave {
+            builtin: false,
+            region_size: 2,
+            attrs: v10::ChipAttrs {
+                name: String::from("Pins"),
+                category: String::from("Basic"),
+                logic: Logic::Combinational,
+                pin_layout: v10::PinLayout {
+                    l_nodes: vec![top(1), top(0)],
+                    r_nodes: vec![top(2)],
+                },
+            },
+            scene: None,
+            l_nodes: vec![pin("a"), pin("b")],
+            r_nodes: vec![],
+            inner_nodes: vec![],
+            natives: vec![],
+            tests: vec![],
+        };
+        let project = v10::Project {
+            name: String::from("Pins"),
+            scenes: vec![],
+            library: v10::Library {
+                tables: vec![],
+                chips: vec![save],
+            },
+        };
+        let project = decode_project(&encode(10, &project)).unwrap();
+        let layout = &project.library.chips[0].attrs.pin_layout;
+        assert_eq!(layout.get(Side::Left, "a", 0), top(1));
+        assert_eq!(layout.get(Side::Left, "b", 1), top(0));
+        // The right placement had no pin, so it's dropped.
+        assert!(layout.r_nodes.is_empty());
+    }
+
+    #[test]
+    fn attaches_junctions_to_their_parent() {
+        let external = |x: f32, names: &[&str]| ExternalNodes {
+            pos: Vec2::new(x, 0.0),
+            states: (names.iter().enumerate())
+                .map(|(idx, name)| (NodeAddr(idx as u32), String::from(*name)))
+                .collect(),
+        };
+        let wire = |output, junction| v11::Wire {
+            input: NodeIdent::LExternal(0),
+            output: NodeIdent::RExternal(output),
+            anchors: vec![],
+            routed: false,
+            junction,
+        };
+        // The wires go from (10, 10) to (100, 10) and (100, 30)
+        let scene = v11::Scene {
+            sim: Default::default(),
+            save_attrs: Default::default(),
+            transform: Default::default(),
+            l_nodes: external(0.0, &["a"]),
+            r_nodes: external(90.0, &["x", "y"]),
+            devices: Default::default(),
+            wires: vec![wire(0, None), wire(1, Some(Vec2::new(40.0, 10.0)))],
+            wire_bundles: vec![],
+            paused: false,
+            snapshots: vec![],
+            breakpoints: vec![],
+        };
+        let project = v11::Project {
+            name: String::from("Branches"),
+            scenes: vec![scene],
+            library: v11::Library {
+                tables: vec![],
+                chips: vec![],
+            },
+        };
+        let project = decode_project(&encode(11, &project)).unwrap();
+        let scene = &project.scenes[0];
+        let junction = scene.wires[1].junction.expect("the junction was dropped");
+        assert_eq!(junction.parent, NodeIdent::RExternal(0));
+        assert!((junction.along - 1.0 / 3.0).abs() < 1e-4);
+        assert!(scene.wire_start(1).unwrap().distance(Vec2::new(40.0, 10.0)) < 1e-3);
+    }
+
+    #[test]
+    fn keeps_tables_without_dont_cares() {
+        let project = v12::Project {
+            name: String::from("Tables"),
+            scenes: vec![],
+            library: v12::Library {
+                tables: vec![v12::TruthTable {
+                    num_inputs: 1,
+                    num_outputs: 1,
+                    name: String::from("Not"),
+                    map: Box::new([1, 0]),
+                }],
+                chips: vec![],
+            },
+        };
+        let project = decode_project(&encode(12, &project)).unwrap();
+        let table = &project.library.tables[0];
+        assert_eq!((table.name.as_str(), &*table.map), ("Not", &[1, 0][..]));
+        assert!(table.dont_care.is_empty());
+    }
+}
@@ -1,8 +1,13 @@
+use crate::geom::{Rect, Transform};
+use crate::sim::debug::Breakpoint;
+use crate::sim::native::{
+    Clock, DisplayConfig, Keyboard, MemoryConfig, NativeDevice, PixelDisplay, Probe, Ram, Rom,
+    Terminal,
+};
 use crate::sim::save::ChipAttrs;
-use crate::sim::{save, NodeAddr, NodeRegion, Sim};
-use crate::ui::Transform;
-
-use egui::Rect;
+use crate::sim::snapshot::{NamedSnapshot, Timeline};
+use crate::sim::wave::Signal;
+use crate::sim::{save, CopySource, Node, NodeAddr, NodeRegion, Sim, Source, SourceTy, TruthTable};
 
 use glam::{vec2, Vec2};
 use serde::{Deserialize, Serialize};
@@ -11,6 +16,8 @@ use std::collections::HashMap;
 
 pub const UNIT: f32 = 20.0;
 pub const CHIP_W: f32 = UNIT * 2.0;
+/// The size of one pixel of a pixel display.
+pub const PIXEL_SIZE: f32 = UNIT * 0.5;
 
 pub type SceneId = crate::Id;
 
@@ -33,7 +40,176 @@ impl Rotation {
     }
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+/// Horizontal and vertical mirroring of a placed device.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Mirror {
+    pub h: bool,
+    pub v: bool,
+}
+impl Mirror {
+    pub fn edge(self, edge: Edge) -> Edge {
+        match edge {
+            Edge::Left | Edge::Right if self.h => edge.opposite(),
+            Edge::Top | Edge::Bottom if self.v => edge.opposite(),
+            edge => edge,
+        }
+    }
+
+    pub fn apply(self, center: Vec2, p: Vec2) -> Vec2 {
+        let x = if self.h { center.x * 2.0 - p.x } else { p.x };
+        let y = if self.v { center.y * 2.0 - p.y } else { p.y };
+        vec2(x, y)
+    }
+}
+
+/// An edge of a device that pins can be placed on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Edge {
+    Left,
+    Top,
+    Right,
+    Bottom,
+}
+impl Edge {
+    pub const ALL: [Self; 4] = [Self::Left, Self::Top, Self::Right, Self::Bottom];
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Left => Self::Top,
+            Self::Top => Self::Right,
+            Self::Right => Self::Bottom,
+            Self::Bottom => Self::Left,
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Self::Left => Self::Right,
+            Self::Top => Self::Bottom,
+            Self::Right => Self::Left,
+            Self::Bottom => Self::Top,
+        }
+    }
+
+    /// The direction a wire should leave a pin on this edge.
+    pub fn normal(self) -> Vec2 {
+        match self {
+            Self::Left => vec2(-1.0, 0.0),
+            Self::Top => vec2(0.0, -1.0),
+            Self::Right => vec2(1.0, 0.0),
+            Self::Bottom => vec2(0.0, 1.0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PinPlacement {
+    pub edge: Edge,
+    /// Pins on the same edge are sorted by this value.
+    pub order: u32,
+}
+
+/// The left and right pins of a device.
+pub type DevicePins<'a> = [&'a [(NodeAddr, String, save::IoType)]; 2];
+
+/// Where each of a chips external pins are placed on it's edges, by the name of the pin.
+/// Pins without an entry default to inputs on the left and outputs on the right.
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct PinLayout {
+    pub l_nodes: Vec<(String, PinPlacement)>,
+    pub r_nodes: Vec<(String, PinPlacement)>,
+}
+impl PinLayout {
+    /// The placement of the pin `name`, which is the `idx`th pin on it's side.
+    pub fn get(&self, side: Side, name: &str, idx: u32) -> PinPlacement {
+        let (list, edge) = match side {
+            Side::Left => (&self.l_nodes, Edge::Left),
+            Side::Right => (&self.r_nodes, Edge::Right),
+        };
+        (list.iter())
+            .find(|(pin, _)| pin == name)
+            .map(|(_, placement)| *placement)
+            .unwrap_or(PinPlacement { edge, order: idx })
+    }
+
+    pub fn set(&mut self, side: Side, name: &str, placement: PinPlacement) {
+        let list = match side {
+            Side::Left => &mut self.l_nodes,
+            Side::Right => &mut self.r_nodes,
+        };
+        match list.iter_mut().find(|(pin, _)| pin == name) {
+            Some((_, p)) => *p = placement,
+            None => list.push((name.into(), placement)),
+        }
+    }
+
+    fn placements<'a>(
+        &'a self,
+        pins: DevicePins<'a>,
+    ) -> impl Iterator<Item = (Side, u32, PinPlacement)> + 'a {
+        [Side::Left, Side::Right]
+            .into_iter()
+            .zip(pins)
+            .flat_map(move |(side, nodes)| {
+                (nodes.iter().enumerate()).map(move |(idx, (_, name, _))| {
+                    (side, idx as u32, self.get(side, name, idx as u32))
+                })
+            })
+    }
+
+    /// Returns the edge a pin is on, and it's index along that edge.
+    pub fn slot(&self, side: Side, idx: u32, pins: DevicePins) -> (Edge, u32) {
+        let key = |side: Side, idx: u32, p: PinPlacement| (p.order, side as u8, idx);
+        let (_, name, _) = &pins[side as usize][idx as usize];
+        let this = self.get(side, name, idx);
+        let this_key = key(side, idx, this);
+
+        let slot = (self.placements(pins))
+            .filter(|(side, idx, p)| p.edge == this.edge && key(*side, *idx, *p) < this_key)
+            .count();
+        (this.edge, slot as u32)
+    }
+
+    /// The number of pins on each edge, indexed by `Edge as usize`.
+    pub fn edge_counts(&self, pins: DevicePins) -> [u32; 4] {
+        let mut out = [0; 4];
+        for (_, _, p) in self.placements(pins) {
+            out[p.edge as usize] += 1;
+        }
+        out
+    }
+
+    /// The smallest size of a device that can fit all of it's pins.
+    pub fn min_size(&self, pins: DevicePins) -> Vec2 {
+        let c = self.edge_counts(pins);
+        let w = c[Edge::Top as usize].max(c[Edge::Bottom as usize]) as f32 * UNIT;
+        let h = c[Edge::Left as usize].max(c[Edge::Right as usize]) as f32 * UNIT;
+        vec2(w, h)
+    }
+
+    /// Computes the position of a pin on a device with the given `bounds`.
+    pub fn pin_pos(
+        &self,
+        bounds: Rect,
+        mirror: Mirror,
+        side: Side,
+        idx: u32,
+        pins: DevicePins,
+    ) -> (Vec2, Edge) {
+        let (edge, slot) = self.slot(side, idx, pins);
+        let along = slot as f32 * UNIT + UNIT * 0.5;
+        let pos = match edge {
+            Edge::Left => vec2(bounds.min.x, bounds.min.y + along),
+            Edge::Right => vec2(bounds.max.x, bounds.min.y + along),
+            Edge::Top => vec2(bounds.min.x + along, bounds.min.y),
+            Edge::Bottom => vec2(bounds.min.x + along, bounds.max.y),
+        };
+        let center = vec2(bounds.center().x, bounds.center().y);
+        (mirror.apply(center, pos), mirror.edge(edge))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum NodeIdent {
     LExternal(u32),
     RExternal(u32),
@@ -46,6 +222,64 @@ pub struct Wire {
     pub input: NodeIdent,
     pub output: NodeIdent,
     pub anchors: Vec<Vec2>,
+    /// If the anchors were computed by the router, and should be recomputed when a connected device moves.
+    pub routed: bool,
+    /// If the wire branches off of another wire driven by the same `input`, this is where on that
+    /// wire it starts.
+    pub junction: Option<Junction>,
+}
+impl Wire {
+    pub fn new(input: NodeIdent, output: NodeIdent, anchors: Vec<Vec2>) -> Self {
+        Self {
+            input,
+            output,
+            anchors,
+            routed: false,
+            junction: None,
+        }
+    }
+}
+
+/// Where a wire branches off of another wire.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Junction {
+    /// The node the wire that's branched off of goes into, which only one wire can drive.
+    pub parent: NodeIdent,
+    /// How far along the parent wire the junction is, from 0 at it's start to 1 at it's end.
+    pub along: f32,
+}
+
+/// The point `along` the way through a line through `points`, from 0 to 1.
+fn point_along(points: &[Vec2], along: f32) -> Vec2 {
+    let len: f32 = points.windows(2).map(|w| w[0].distance(w[1])).sum();
+    let mut left = along.clamp(0.0, 1.0) * len;
+    for w in points.windows(2) {
+        let segment = w[0].distance(w[1]);
+        if left <= segment && segment > 0.0 {
+            return w[0].lerp(w[1], left / segment);
+        }
+        left -= segment;
+    }
+    points.last().copied().unwrap_or_default()
+}
+
+/// How far along a line through `points` the point `pos` on the segment `segment` is, from 0 to 1.
+pub(crate) fn fraction_along(points: &[Vec2], segment: usize, pos: Vec2) -> f32 {
+    let len: f32 = points.windows(2).map(|w| w[0].distance(w[1])).sum();
+    let before: f32 = (points.windows(2).take(segment))
+        .map(|w| w[0].distance(w[1]))
+        .sum();
+    match len > 0.0 {
+        true => ((before + points[segment].distance(pos)) / len).clamp(0.0, 1.0),
+        false => 0.0,
+    }
+}
+
+/// One of the two ends of a [Wire].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WireEnd {
+    Input,
+    Output,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -55,7 +289,7 @@ pub struct WireBundle {
     pub anchors: Vec<Vec2>,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 #[repr(u8)]
 pub enum Side {
     Left,
@@ -68,13 +302,13 @@ pub struct ExternalNodes {
     pub states: Vec<(NodeAddr, String)>,
 }
 impl ExternalNodes {
-    pub fn node_info(&self, idx: u32) -> Option<NodeInfo> {
+    pub fn node_info(&self, idx: u32, edge: Edge) -> Option<NodeInfo> {
         let addr = self.states.get(idx as usize)?.0;
         let pos = vec2(
             self.pos.x + UNIT * 0.5,
             self.pos.y + UNIT * idx as f32 + UNIT * 0.5,
         );
-        Some(NodeInfo { addr, pos })
+        Some(NodeInfo { addr, pos, edge })
     }
 }
 
@@ -97,6 +331,16 @@ pub struct Scene {
     pub devices: HashMap<SceneId, Device>,
     pub wires: Vec<Wire>,
     pub wire_bundles: Vec<WireBundle>,
+    /// If the simulation isn't updated every frame.
+    pub paused: bool,
+    #[serde(skip)]
+    pub timeline: Timeline,
+    /// Snapshots the user saved to come back to later.
+    pub snapshots: Vec<NamedSnapshot>,
+    pub breakpoints: Vec<Breakpoint>,
+    /// The index of the breakpoint that paused the simulation.
+    #[serde(skip)]
+    pub hit_breakpoint: Option<usize>,
 }
 impl Scene {
     pub fn clear(&mut self) {
@@ -106,6 +350,55 @@ impl Scene {
         self.wires.clear();
         self.wire_bundles.clear();
         self.sim.clear();
+        self.timeline = Default::default();
+    }
+
+    /// Updates the simulation by one tick, taking a periodic snapshot if one is due,
+    /// and pausing if a breakpoint was hit.
+    pub fn step(&mut self, tables: &[TruthTable]) {
+        let watched: Vec<_> = (self.breakpoints.iter())
+            .map(|bp| {
+                let addr = self.node_info(bp.node).filter(|_| bp.enabled)?.addr;
+                Some((addr, self.sim.get_node(addr).state()))
+            })
+            .collect();
+
+        self.sim.update(tables);
+        self.timeline.update(&self.sim);
+
+        for (idx, (bp, watched)) in self.breakpoints.iter().zip(watched).enumerate() {
+            let Some((addr, prev)) = watched else {
+                continue;
+            };
+            if bp
+                .condition
+                .triggered(prev, self.sim.get_node(addr).state())
+            {
+                self.paused = true;
+                self.hit_breakpoint = Some(idx);
+                break;
+            }
+        }
+    }
+
+    /// A name for a node, made of the device and pin names.
+    pub fn node_label(&self, ident: NodeIdent) -> String {
+        let device_pin = |id: SceneId, idx: u32, left: bool| {
+            let device = self.devices.get(&id)?;
+            let nodes = if left {
+                device.l_nodes()
+            } else {
+                device.r_nodes()
+            };
+            Some(format!("{}.{}", device.name(), nodes.get(idx as usize)?.1))
+        };
+        let label = match ident {
+            NodeIdent::LExternal(idx) => self.l_nodes.states.get(idx as usize).map(|s| s.1.clone()),
+            NodeIdent::RExternal(idx) => self.r_nodes.states.get(idx as usize).map(|s| s.1.clone()),
+            NodeIdent::DeviceL(id, idx) => device_pin(id, idx, true),
+            NodeIdent::DeviceR(id, idx) => device_pin(id, idx, false),
+        };
+        label.unwrap_or_else(|| String::from("(missing)"))
     }
 
     pub fn init(&mut self, view: Rect) {
@@ -113,35 +406,524 @@ impl Scene {
         // self.r_nodes.pos = vec2(view.max.x - BG_NODE_SIZE, view.min.y + view.height() * 0.3);
     }
 
-    pub fn rm_wire_by_target(&mut self, output: NodeAddr) -> Option<Wire> {
-        for idx in 0..self.wires.len() {
-            if let Some(info) = self.node_info(self.wires[idx].output) {
-                if info.addr == output {
-                    return Some(self.wires.remove(idx));
+    /// If `ident` is the output of a pull device, whose wires only drive a node when no other
+    /// wire goes into it.
+    pub fn is_pull(&self, ident: NodeIdent) -> bool {
+        match ident {
+            NodeIdent::DeviceR(id, _) => matches!(
+                self.devices.get(&id),
+                Some(Device::Builtin(builtin)) if builtin.ty.is_pull()
+            ),
+            _ => false,
+        }
+    }
+
+    /// The index of the wire from `input` to `output`.
+    pub fn find_wire(&self, input: NodeIdent, output: NodeIdent) -> Option<usize> {
+        (self.wires.iter()).position(|wire| wire.input == input && wire.output == output)
+    }
+
+    /// Removes the wire going into `output` that a new wire from `input` replaces. A node can have
+    /// one wire from a pull device, and one wire from anything else.
+    pub fn rm_wire_by_target(&mut self, output: NodeAddr, input: NodeIdent) -> Option<Wire> {
+        let pull = self.is_pull(input);
+        let idx = (0..self.wires.len()).find(|idx| {
+            let wire = &self.wires[*idx];
+            self.is_pull(wire.input) == pull
+                && self
+                    .node_info(wire.output)
+                    .is_some_and(|info| info.addr == output)
+        })?;
+        Some(self.remove_wire(idx))
+    }
+
+    /// Removes the wire at `idx`, along with the wires that branch off of it, and reconnects the
+    /// node it drove.
+    pub fn remove_wire(&mut self, idx: usize) -> Wire {
+        let wire = self.wires.remove(idx);
+        while let Some(branch) = self.branches(wire.input, wire.output).first() {
+            self.remove_wire(*branch);
+        }
+        self.connect_node(wire.output);
+        wire
+    }
+
+    /// Sets the [Source] of the node `output` to the wire going into it, preferring a wire that
+    /// isn't from a pull device.
+    pub fn connect_node(&mut self, output: NodeIdent) {
+        let Some(dst) = self.node_info(output) else {
+            return;
+        };
+        let inputs = (self.wires.iter())
+            .filter(|wire| wire.output == output)
+            .map(|wire| wire.input);
+        let driver = (inputs.clone().find(|input| !self.is_pull(*input)))
+            .or(inputs.clone().next())
+            .and_then(|input| self.node_info(input));
+        let src = driver.map_or(Source::new_none(), |src| Source::new_addr(src.addr));
+        self.sim.set_node_src(dst.addr, src);
+    }
+
+    /// The indices of the wires that branch off of the wire from `input` to `output`.
+    pub fn branches(&self, input: NodeIdent, output: NodeIdent) -> Vec<usize> {
+        (0..self.wires.len())
+            .filter(|idx| {
+                let wire = &self.wires[*idx];
+                wire.input == input && wire.junction.is_some_and(|j| j.parent == output)
+            })
+            .collect()
+    }
+
+    /// Where the wire at `idx` starts, which is on it's parent wire if it's a branch.
+    /// Returns None if a node or parent wire it's connected to doesn't exist.
+    pub fn wire_start(&self, idx: usize) -> Option<Vec2> {
+        self.wire_start_within(idx, self.wires.len())
+    }
+
+    /// Like [Self::wire_start], looking at most `depth` parents up, in case of a loop.
+    fn wire_start_within(&self, idx: usize, depth: usize) -> Option<Vec2> {
+        let wire = &self.wires[idx];
+        match wire.junction {
+            Some(junction) => self.junction_pos_within(wire.input, junction, depth),
+            None => Some(self.node_info(wire.input)?.pos),
+        }
+    }
+
+    /// The point a junction is at, on it's parent wire which is driven by `input`.
+    pub fn junction_pos(&self, input: NodeIdent, junction: Junction) -> Option<Vec2> {
+        self.junction_pos_within(input, junction, self.wires.len())
+    }
+
+    fn junction_pos_within(
+        &self,
+        input: NodeIdent,
+        junction: Junction,
+        depth: usize,
+    ) -> Option<Vec2> {
+        let parent = self.find_wire(input, junction.parent)?;
+        let points = self.wire_points_within(parent, depth.checked_sub(1)?)?;
+        Some(point_along(&points, junction.along))
+    }
+
+    /// The points a wire goes through, from it's start to it's end.
+    pub fn wire_points(&self, idx: usize) -> Option<Vec<Vec2>> {
+        self.wire_points_within(idx, self.wires.len())
+    }
+
+    fn wire_points_within(&self, idx: usize, depth: usize) -> Option<Vec<Vec2>> {
+        let wire = &self.wires[idx];
+        let mut points = vec![self.wire_start_within(idx, depth)?];
+        points.extend(&wire.anchors);
+        points.push(self.node_info(wire.output)?.pos);
+        Some(points)
+    }
+
+    /// The junction for a branch off of the wire at `idx`, at the point `pos` on it's segment
+    /// `segment`.
+    pub fn junction_at(&self, idx: usize, segment: usize, pos: Vec2) -> Option<Junction> {
+        let points = self.wire_points(idx)?;
+        (segment + 1 < points.len()).then(|| Junction {
+            parent: self.wires[idx].output,
+            along: fraction_along(&points, segment, pos),
+        })
+    }
+
+    /// Finds the node that is within half a grid unit of `pos`.
+    pub fn node_at(&self, pos: Vec2) -> Option<NodeIdent> {
+        let external = (0..self.l_nodes.states.len() as u32)
+            .map(NodeIdent::LExternal)
+            .chain((0..self.r_nodes.states.len() as u32).map(NodeIdent::RExternal));
+        let device = self.devices.iter().flat_map(|(id, device)| {
+            let l = (0..device.l_nodes().len() as u32).map(|idx| NodeIdent::DeviceL(*id, idx));
+            let r = (0..device.r_nodes().len() as u32).map(|idx| NodeIdent::DeviceR(*id, idx));
+            l.chain(r)
+        });
+        external.chain(device).find(|ident| {
+            self.node_info(*ident)
+                .is_some_and(|info| info.pos.distance(pos) <= UNIT * 0.5)
+        })
+    }
+
+    /// Moves one end of the wire at `idx` to the node `ident`, updating the [Source] of the
+    /// node the wire drives.
+    /// Returns false if the wire couldn't be connected to `ident`.
+    pub fn reconnect_wire(&mut self, idx: usize, end: WireEnd, ident: NodeIdent) -> bool {
+        let wire = &self.wires[idx];
+        if ident == wire.input || ident == wire.output {
+            return false;
+        }
+        let (Some(_), Some(_), Some(new)) = (
+            self.node_info(wire.input),
+            self.node_info(wire.output),
+            self.node_info(ident),
+        ) else {
+            return false;
+        };
+
+        let mut wire = self.wires.remove(idx);
+        let (input, output) = (wire.input, wire.output);
+        match end {
+            WireEnd::Input => {
+                wire.input = ident;
+                wire.junction = None;
+
+                // The wires branching off of this one are driven by the new node too
+                let mut parents = vec![output];
+                let mut outputs = vec![output];
+                while let Some(parent) = parents.pop() {
+                    for branch in self.branches(input, parent) {
+                        self.wires[branch].input = ident;
+                        parents.push(self.wires[branch].output);
+                        outputs.push(self.wires[branch].output);
+                    }
+                }
+                self.wires.push(wire);
+                for output in outputs {
+                    self.connect_node(output);
+                }
+            }
+            WireEnd::Output => {
+                // A node can only have one source, so replace the wire that was driving it.
+                _ = self.rm_wire_by_target(new.addr, input);
+                for branch in self.branches(input, output) {
+                    if let Some(junction) = &mut self.wires[branch].junction {
+                        junction.parent = ident;
+                    }
+                }
+                wire.output = ident;
+                // The wire it branched off of could have been the one that was replaced
+                if let Some(junction) = wire.junction {
+                    if self.find_wire(input, junction.parent).is_none() {
+                        wire.junction = None;
+                    }
                 }
+                self.wires.push(wire);
+                self.connect_node(output);
+                self.connect_node(ident);
             }
         }
-        None
+        let routed = self.wires.last().is_some_and(|wire| wire.routed);
+        if routed {
+            _ = self.route_wire(self.wires.len() - 1);
+        }
+        true
     }
 
     pub fn node_info(&self, ident: NodeIdent) -> Option<NodeInfo> {
         match ident {
-            NodeIdent::LExternal(idx) => self.l_nodes.node_info(idx),
-            NodeIdent::RExternal(idx) => self.r_nodes.node_info(idx),
+            NodeIdent::LExternal(idx) => self.l_nodes.node_info(idx, Edge::Right),
+            NodeIdent::RExternal(idx) => self.r_nodes.node_info(idx, Edge::Left),
             NodeIdent::DeviceL(id, idx) => self.devices.get(&id)?.node_info(Side::Left, idx),
             NodeIdent::DeviceR(id, idx) => self.devices.get(&id)?.node_info(Side::Right, idx),
         }
     }
 
-    pub fn add_device(&mut self, device: impl Into<Device>) {
-        self.devices
-            .insert(SceneId::new(fastrand::u32(..)), device.into());
+    /// Computes the anchors of the wire at `idx` using the auto-router.
+    /// Returns false if no route could be found, leaving the wire unchanged.
+    pub fn route_wire(&mut self, idx: usize) -> bool {
+        use crate::sim::route::{route, Endpoint};
+
+        let wire = &self.wires[idx];
+        let (Some(src), Some(dst)) = (self.node_info(wire.input), self.node_info(wire.output))
+        else {
+            return false;
+        };
+        let Some(start) = self.wire_start(idx) else {
+            return false;
+        };
+        let obstacles: Vec<Rect> = self.devices.values().map(Device::bounds).collect();
+        let start = Endpoint {
+            pos: start,
+            edge: src.edge,
+        };
+        let end = Endpoint {
+            pos: dst.pos,
+            edge: dst.edge,
+        };
+        let Some(anchors) = route(start, end, &obstacles) else {
+            return false;
+        };
+        self.wires[idx].anchors = anchors;
+        self.wires[idx].routed = true;
+
+        // The routed wires that branch off of this one start somewhere else now
+        let wire = &self.wires[idx];
+        for branch in self.branches(wire.input, wire.output) {
+            if self.wires[branch].routed {
+                _ = self.route_wire(branch);
+            }
+        }
+        true
+    }
+
+    /// Reroutes the auto-routed wires connected to the device `id`, and the ones that now go
+    /// through it.
+    pub fn reroute_device(&mut self, id: SceneId) {
+        let Some(bounds) = self.devices.get(&id).map(Device::bounds) else {
+            return;
+        };
+        let connected = |ident: NodeIdent| match ident {
+            NodeIdent::DeviceL(dev, _) | NodeIdent::DeviceR(dev, _) => dev == id,
+            _ => false,
+        };
+        for idx in 0..self.wires.len() {
+            let wire = &self.wires[idx];
+            if !wire.routed {
+                continue;
+            }
+            let crosses = || {
+                (self.wire_points(idx)).is_some_and(|points| {
+                    (points.windows(2)).any(|line| bounds.intersects_line(line[0], line[1]))
+                })
+            };
+            if connected(wire.input) || connected(wire.output) || crosses() {
+                _ = self.route_wire(idx);
+            }
+        }
+    }
+
+    /// Removes a device, along with any native devices in it's node region.
+    pub fn remove_device(&mut self, id: SceneId) -> Option<Device> {
+        let device = self.devices.remove(&id)?;
+        let region = device.region();
+        self.sim
+            .natives
+            .retain(|native| !(region.min.0..region.max.0).contains(&native.base().0));
+        Some(device)
+    }
+
+    /// Recreates the builtin device `id` with the configuration `data`,
+    /// keeping the wires connected to it's pins.
+    pub fn reconfigure_builtin(&mut self, id: SceneId, data: BuiltinData) {
+        let Some(Device::Builtin(old)) = self.devices.get(&id) else {
+            return;
+        };
+        let (ty, pos, rotation, mirror) = (old.ty, old.pos, old.rotation, old.mirror);
+        let old_region = old.region.clone();
+        let old_native = self.sim.native(old.region.min).cloned();
+        _ = self.remove_device(id);
+
+        // Reuse the old nodes if there are enough of them, so changing the device doesn't leak
+        let size = BuiltinDevice::region_size(ty, &data);
+        let region = match old_region.max.0 - old_region.min.0 >= size {
+            true => old_region,
+            false => {
+                self.sim.free_region(&old_region);
+                self.sim.alloc_region(size)
+            }
+        };
+        let mut device = BuiltinDevice::in_region(ty, data, &mut self.sim, pos, region);
+        device.rotation = rotation;
+        device.mirror = mirror;
+        if let Some((old, native)) = old_native.zip(self.sim.native_mut(device.region.min)) {
+            native.restore(&old);
+        }
+        self.devices.insert(id, device.into());
+
+        // Pins that no longer exist lose their wires, and the rest are connected to the new nodes
+        let outputs: Vec<_> = self.wires.iter().map(|wire| wire.output).collect();
+        let keep: Vec<_> = (self.wires.iter())
+            .map(|wire| {
+                self.node_info(wire.input).is_some() && self.node_info(wire.output).is_some()
+            })
+            .collect();
+        let mut keep = keep.into_iter();
+        self.wires.retain(|_| keep.next().unwrap());
+        for output in outputs {
+            self.connect_node(output);
+        }
+    }
+
+    pub fn add_device(&mut self, device: impl Into<Device>) -> SceneId {
+        let id = SceneId::new(fastrand::u32(..));
+        self.devices.insert(id, device.into());
+        id
+    }
+
+    /// Groups all of the tunnels in the scene by their names.
+    pub fn nets(&self) -> Vec<Net> {
+        let mut nets: Vec<Net> = vec![];
+        for (id, device) in &self.devices {
+            let Device::Builtin(BuiltinDevice {
+                data: BuiltinData::Tunnel(name),
+                ..
+            }) = device
+            else {
+                continue;
+            };
+            let net = match nets.iter_mut().position(|net| &net.name == name) {
+                Some(idx) => &mut nets[idx],
+                None => {
+                    nets.push(Net {
+                        name: name.clone(),
+                        tunnels: vec![],
+                        drivers: vec![],
+                        error: None,
+                    });
+                    nets.last_mut().unwrap()
+                }
+            };
+            net.tunnels.push(*id);
+            // A tunnel is driving the net if a wire is going into it
+            if self
+                .wires
+                .iter()
+                .any(|wire| wire.output == NodeIdent::DeviceR(*id, 0))
+            {
+                net.drivers.push(*id);
+            }
+        }
+
+        let external_names: Vec<&str> = (self.l_nodes.states.iter())
+            .chain(self.r_nodes.states.iter())
+            .map(|(_, name)| name.as_str())
+            .collect();
+        for net in &mut nets {
+            net.error = if net.name.is_empty() || external_names.contains(&net.name.as_str()) {
+                Some(NetError::NameCollision)
+            } else if net.drivers.len() > 1 {
+                Some(NetError::MultipleDrivers)
+            } else if net.drivers.is_empty() {
+                Some(NetError::NoDriver)
+            } else {
+                None
+            };
+        }
+        nets.sort_by(|a, b| a.name.cmp(&b.name));
+        nets
+    }
+
+    /// The nodes of the scene that can be recorded: the external pins, probes, nets, the pins of
+    /// chips and the nodes driving wires, scoped by the scene and chip names.
+    pub fn signal_candidates(&self) -> Vec<Signal> {
+        let scene = self.save_attrs.name.clone();
+        let signal = |name: &str, scope: &[&String], addr: NodeAddr| Signal {
+            name: String::from(name),
+            scope: scope.iter().map(|s| s.to_string()).collect(),
+            addr,
+            width: 1,
+        };
+        let mut out = vec![];
+        for (addr, name) in self.l_nodes.states.iter().chain(&self.r_nodes.states) {
+            out.push(signal(name, &[&scene], *addr));
+        }
+
+        let mut ids: Vec<_> = self.devices.keys().copied().collect();
+        ids.sort_by_key(|id| id.0);
+        let mut chip_names: HashMap<&str, u32> = HashMap::new();
+        let mut probes = 0;
+        for id in ids {
+            match &self.devices[&id] {
+                Device::Builtin(builtin) if builtin.ty == BuiltinDeviceTy::Probe => {
+                    let name = format!("probe{probes}");
+                    out.push(signal(&name, &[&scene], builtin.l_nodes[0].0));
+                    probes += 1;
+                }
+                Device::Builtin(BuiltinDevice {
+                    data: BuiltinData::Tunnel(net),
+                    r_nodes,
+                    ..
+                }) => {
+                    if !out.iter().any(|signal| &signal.name == net) {
+                        out.push(signal(net, &[&scene], r_nodes[0].0));
+                    }
+                }
+                Device::Builtin(_) => {}
+                Device::Chip(chip) => {
+                    // Chips with the same name get a number so their scopes are unique
+                    let count = chip_names.entry(&chip.attrs.name).or_default();
+                    let chip_scope = match *count {
+                        0 => chip.attrs.name.clone(),
+                        n => format!("{}{n}", chip.attrs.name),
+                    };
+                    *count += 1;
+                    for (addr, name, _) in chip.l_nodes.iter().chain(&chip.r_nodes) {
+                        out.push(signal(name, &[&scene, &chip_scope], *addr));
+                    }
+                }
+            }
+        }
+
+        // Any other wire is named after the pin driving it
+        for wire in &self.wires {
+            let Some(src) = self.node_info(wire.input) else {
+                continue;
+            };
+            if !out.iter().any(|signal| signal.addr == src.addr) {
+                out.push(signal(&self.node_label(wire.input), &[&scene], src.addr));
+            }
+        }
+        out
+    }
+
+    /// Connects the nodes that pull devices are wired to, unless another wire drives them.
+    pub fn connect_pulls(&mut self) {
+        let pulled: Vec<NodeIdent> = (self.wires.iter())
+            .filter(|wire| self.is_pull(wire.input))
+            .map(|wire| wire.output)
+            .collect();
+        for output in pulled {
+            self.connect_node(output);
+        }
+    }
+
+    /// Connects the tunnels of each net, by copying the source of the tunnel driving the net
+    /// to the other tunnels of the net.
+    /// Nets that have an error are left disconnected.
+    pub fn connect_nets(&mut self) {
+        for net in self.nets() {
+            let src = match (net.error, net.drivers.first()) {
+                (None, Some(driver)) => {
+                    let addr = self.devices[driver].r_nodes()[0].0;
+                    self.sim.get_node(addr).source()
+                }
+                _ => Source::new_none(),
+            };
+            for id in &net.tunnels {
+                if net.drivers.contains(id) {
+                    continue;
+                }
+                let addr = self.devices[id].r_nodes()[0].0;
+                self.sim.set_node_src(addr, src);
+            }
+        }
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NetError {
+    /// No tunnel of the net has a wire going into it.
+    NoDriver,
+    /// More than one tunnel of the net has a wire going into it.
+    MultipleDrivers,
+    /// The name of the net is empty, or the same as one of the scenes external nodes.
+    NameCollision,
+}
+impl NetError {
+    pub fn message(self) -> &'static str {
+        match self {
+            Self::NoDriver => "no driver",
+            Self::MultipleDrivers => "multiple drivers",
+            Self::NameCollision => "name collision",
+        }
+    }
+}
+
+/// All of the tunnels in a scene with the same name.
+#[derive(Clone, Debug)]
+pub struct Net {
+    pub name: String,
+    pub tunnels: Vec<SceneId>,
+    /// The tunnels that have a wire going into them.
+    pub drivers: Vec<SceneId>,
+    pub error: Option<NetError>,
+}
+
 pub struct NodeInfo {
     pub pos: Vec2,
     pub addr: NodeAddr,
+    /// The edge of the device the node is on.
+    pub edge: Edge,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
@@ -150,15 +932,89 @@ pub enum BuiltinDeviceTy {
     Button = 0,
     Switch = 1,
     Light = 2,
+    Tunnel = 3,
+    Clock = 4,
+    SevenSegment = 5,
+    HexDisplay = 6,
+    PixelDisplay = 7,
+    Rom = 8,
+    Ram = 9,
+    Keyboard = 10,
+    Terminal = 11,
+    Ground = 12,
+    Power = 13,
+    Constant = 14,
+    PullUp = 15,
+    PullDown = 16,
+    Splitter = 17,
+    Merger = 18,
+    Probe = 19,
 }
 impl BuiltinDeviceTy {
-    pub const COUNT: u8 = 3;
+    pub const COUNT: u8 = 20;
 
     pub fn name(self) -> &'static str {
         match self {
             Self::Button => "Button",
             Self::Switch => "Switch",
             Self::Light => "Light",
+            Self::Tunnel => "Tunnel",
+            Self::Clock => "Clock",
+            Self::SevenSegment => "7-Segment",
+            Self::HexDisplay => "Hex Display",
+            Self::PixelDisplay => "Pixel Display",
+            Self::Rom => "ROM",
+            Self::Ram => "RAM",
+            Self::Keyboard => "Keyboard",
+            Self::Terminal => "Terminal",
+            Self::Ground => "Ground",
+            Self::Power => "Power",
+            Self::Constant => "Constant",
+            Self::PullUp => "Pull-up",
+            Self::PullDown => "Pull-down",
+            Self::Splitter => "Splitter",
+            Self::Merger => "Merger",
+            Self::Probe => "Probe",
+        }
+    }
+
+    pub fn default_data(self) -> BuiltinData {
+        match self {
+            Self::Button => BuiltinData::Button { key: None },
+            Self::Tunnel => BuiltinData::Tunnel(String::from("net")),
+            Self::PixelDisplay => BuiltinData::PixelDisplay(Default::default()),
+            Self::Rom => BuiltinData::Rom(Default::default()),
+            Self::Ram => BuiltinData::Ram(Default::default()),
+            Self::Constant => BuiltinData::Constant { bits: 4, value: 0 },
+            Self::Splitter => BuiltinData::Splitter(Default::default()),
+            Self::Merger => BuiltinData::Merger(Default::default()),
+            _ => BuiltinData::None,
+        }
+    }
+
+    pub fn pin_name(self, side: Side, idx: u32) -> String {
+        match (self, side) {
+            (
+                Self::Tunnel
+                | Self::Ground
+                | Self::Power
+                | Self::PullUp
+                | Self::PullDown
+                | Self::Probe,
+                _,
+            ) => String::new(),
+            (Self::SevenSegment, Side::Left) => {
+                let names = ["a", "b", "c", "d", "e", "f", "g", "dp"];
+                String::from(names[idx as usize])
+            }
+            (Self::HexDisplay, Side::Left) => format!("{}", 1 << idx),
+            (Self::Keyboard, Side::Left) => String::from("ack"),
+            (Self::Keyboard, Side::Right) if idx == Keyboard::CODE_BITS => String::from("ready"),
+            (Self::Keyboard, Side::Right) => format!("k{idx}"),
+            (Self::Terminal, Side::Left) if idx == Terminal::CHAR_BITS => String::from("write"),
+            (Self::Terminal, Side::Left) => format!("c{idx}"),
+            (_, Side::Left) => format!("in{idx}"),
+            (_, Side::Right) => format!("out{idx}"),
         }
     }
 
@@ -171,6 +1027,18 @@ impl BuiltinDeviceTy {
             Self::Button => vec2(20.0, 20.0),
             Self::Switch => vec2(20.0, 20.0),
             Self::Light => vec2(20.0, 20.0),
+            Self::Tunnel => vec2(40.0, 20.0),
+            Self::Clock => vec2(40.0, 20.0),
+            Self::SevenSegment => vec2(UNIT * 3.0, UNIT * 8.0),
+            Self::HexDisplay => vec2(UNIT * 3.0, UNIT * 4.0),
+            Self::PixelDisplay => vec2(UNIT * 6.0, UNIT * 6.0),
+            Self::Rom | Self::Ram => vec2(UNIT * 3.0, UNIT * 3.0),
+            Self::Keyboard => vec2(UNIT * 4.0, UNIT * 2.0),
+            Self::Terminal => vec2(UNIT * 12.0, UNIT * 8.0),
+            Self::Ground | Self::Power | Self::PullUp | Self::PullDown => vec2(UNIT * 2.0, UNIT),
+            Self::Constant => vec2(UNIT * 3.0, UNIT),
+            Self::Splitter | Self::Merger => vec2(UNIT, UNIT),
+            Self::Probe => vec2(UNIT * 8.0, UNIT * 3.0),
         }
     }
 
@@ -179,44 +1047,267 @@ impl BuiltinDeviceTy {
             Self::Button => (0, 1),
             Self::Switch => (0, 1),
             Self::Light => (1, 0),
+            Self::Tunnel => (0, 1),
+            Self::Clock => (0, 1),
+            Self::SevenSegment => (8, 0),
+            Self::HexDisplay => (4, 0),
+            Self::PixelDisplay => (8, 0),
+            Self::Rom => (4, 8),
+            Self::Ram => (14, 8),
+            Self::Keyboard => (1, 8),
+            Self::Terminal => (8, 0),
+            Self::Ground | Self::Power | Self::PullUp | Self::PullDown => (0, 1),
+            Self::Constant => (0, 4),
+            Self::Splitter => (1, 4),
+            Self::Merger => (4, 1),
+            Self::Probe => (1, 0),
+        }
+    }
+
+    /// If the device only drives the nodes it's wired to when no other wire does.
+    pub fn is_pull(self) -> bool {
+        matches!(self, Self::PullUp | Self::PullDown)
+    }
+
+    /// The value a device with a constant output sets it's output pins to, least significant bit first.
+    pub fn constant_value(self, data: &BuiltinData) -> Option<u32> {
+        match (self, data) {
+            (Self::Constant, BuiltinData::Constant { value, .. }) => Some(*value),
+            (Self::Ground | Self::PullDown, _) => Some(0),
+            (Self::Power | Self::PullUp, _) => Some(1),
+            _ => None,
+        }
+    }
+
+    /// The names of the left and right pins of a device with the configuration `data`.
+    pub fn pins(self, data: &BuiltinData) -> (Vec<String>, Vec<String>) {
+        match data {
+            BuiltinData::PixelDisplay(cfg) => (cfg.input_names(), vec![]),
+            BuiltinData::Rom(cfg) => (
+                (0..cfg.addr_bits).map(|idx| format!("a{idx}")).collect(),
+                (0..cfg.data_bits).map(|idx| format!("d{idx}")).collect(),
+            ),
+            BuiltinData::Ram(cfg) => {
+                let mut inputs: Vec<_> = (0..cfg.addr_bits).map(|idx| format!("a{idx}")).collect();
+                inputs.extend((0..cfg.data_bits).map(|idx| format!("din{idx}")));
+                inputs.extend([String::from("we"), String::from("clk")]);
+                let outputs = (0..cfg.data_bits).map(|idx| format!("dout{idx}"));
+                (inputs, outputs.collect())
+            }
+            BuiltinData::Constant { bits, .. } => {
+                (vec![], (0..*bits).map(|idx| format!("out{idx}")).collect())
+            }
+            BuiltinData::Splitter(range) => (vec![String::from("bus")], range.names()),
+            BuiltinData::Merger(range) => (range.names(), vec![String::from("bus")]),
+            _ => {
+                let (inputs, outputs) = self.io();
+                let l = (0..inputs as u32).map(|idx| self.pin_name(Side::Left, idx));
+                let r = (0..outputs as u32).map(|idx| self.pin_name(Side::Right, idx));
+                (l.collect(), r.collect())
+            }
         }
     }
 }
 
+/// A key on the keyboard, as the index of it's variant in `egui::Key`. It's saved the same way
+/// egui saves the key, so the simulation doesn't depend on egui.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Key(pub u32);
+#[cfg(feature = "ui")]
+impl Key {
+    pub fn from_egui(key: egui::Key) -> Self {
+        Self(key as u32)
+    }
+
+    pub fn to_egui(self) -> Option<egui::Key> {
+        egui::Key::ALL
+            .iter()
+            .copied()
+            .find(|key| *key as u32 == self.0)
+    }
+}
+
+/// The configuration of a builtin device.
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub enum BuiltinData {
+    #[default]
+    None,
+    /// The key that holds the button down while pressed.
+    Button {
+        key: Option<Key>,
+    },
+    /// The name of the net the tunnel connects to.
+    Tunnel(String),
+    PixelDisplay(DisplayConfig),
+    Rom(MemoryConfig),
+    Ram(MemoryConfig),
+    /// A number that is output on `bits` pins.
+    Constant {
+        bits: u32,
+        value: u32,
+    },
+    /// The bits of the bus that are split into pins.
+    Splitter(BitRange),
+    /// The bits of the bus that the pins are merged into.
+    Merger(BitRange),
+}
+
+/// An inclusive range of the bits of a multi-bit node.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BitRange {
+    pub lo: u8,
+    pub hi: u8,
+}
+impl Default for BitRange {
+    fn default() -> Self {
+        Self { lo: 0, hi: 3 }
+    }
+}
+impl BitRange {
+    /// The largest bit index a node can hold.
+    pub const MAX_BIT: u8 = 7;
+
+    pub fn count(self) -> u8 {
+        self.hi.saturating_sub(self.lo) + 1
+    }
+
+    fn names(self) -> Vec<String> {
+        (self.lo..=self.hi).map(|bit| format!("b{bit}")).collect()
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct BuiltinDevice {
     pub ty: BuiltinDeviceTy,
+    pub data: BuiltinData,
     pub region: NodeRegion,
     pub pos: Vec2,
     pub rotation: Rotation,
+    pub mirror: Mirror,
     pub l_nodes: Vec<(NodeAddr, String, save::IoType)>,
     pub r_nodes: Vec<(NodeAddr, String, save::IoType)>,
 }
 impl BuiltinDevice {
+    /// Creates a builtin device, allocating it's nodes in `sim`.
+    pub fn new(ty: BuiltinDeviceTy, sim: &mut Sim, pos: Vec2) -> Self {
+        Self::with_data(ty, ty.default_data(), sim, pos)
+    }
+
+    /// Creates a builtin device with the configuration `data`, allocating it's nodes in `sim`.
+    pub fn with_data(ty: BuiltinDeviceTy, data: BuiltinData, sim: &mut Sim, pos: Vec2) -> Self {
+        let region = sim.alloc_region(Self::region_size(ty, &data));
+        Self::in_region(ty, data, sim, pos, region)
+    }
+
+    /// The number of nodes a device needs for it's pins.
+    pub fn region_size(ty: BuiltinDeviceTy, data: &BuiltinData) -> u32 {
+        let (l_names, r_names) = ty.pins(data);
+        (l_names.len() + r_names.len()) as u32
+    }
+
+    /// Like [Self::with_data], with it's pins at the start of `region`, which has to have at
+    /// least [Self::region_size] nodes.
+    pub fn in_region(
+        ty: BuiltinDeviceTy,
+        data: BuiltinData,
+        sim: &mut Sim,
+        pos: Vec2,
+        region: NodeRegion,
+    ) -> Self {
+        for addr in region.min.0..region.max.0 {
+            sim.set_node(NodeAddr(addr), Node::default());
+        }
+        let (l_names, r_names) = ty.pins(&data);
+        let mut addrs = region.min.0..;
+        let mut alloc =
+            |name: String, io: save::IoType| (NodeAddr(addrs.next().unwrap()), name, io);
+        let l_nodes: Vec<_> = (l_names.into_iter())
+            .map(|name| alloc(name, save::IoType::Input))
+            .collect();
+        let r_nodes: Vec<_> = (r_names.into_iter())
+            .map(|name| alloc(name, save::IoType::Output))
+            .collect();
+
+        let native = match &data {
+            BuiltinData::PixelDisplay(cfg) => Some(NativeDevice::PixelDisplay(PixelDisplay::new(
+                region.min, *cfg,
+            ))),
+            BuiltinData::Rom(cfg) => Some(NativeDevice::Rom(Rom::new(region.min, *cfg))),
+            BuiltinData::Ram(cfg) => Some(NativeDevice::Ram(Ram::new(region.min, *cfg))),
+            _ => match ty {
+                BuiltinDeviceTy::Clock => Some(NativeDevice::Clock(Clock::new(region.min))),
+                BuiltinDeviceTy::Keyboard => {
+                    Some(NativeDevice::Keyboard(Keyboard::new(region.min)))
+                }
+                BuiltinDeviceTy::Terminal => {
+                    Some(NativeDevice::Terminal(Terminal::new(region.min)))
+                }
+                BuiltinDeviceTy::Probe => Some(NativeDevice::Probe(Probe::new(region.min))),
+                _ => None,
+            },
+        };
+        sim.natives.extend(native);
+
+        // Splitters and mergers are only copy sources, so they don't cost anything extra to update
+        match &data {
+            BuiltinData::Splitter(range) => {
+                for (idx, (addr, ..)) in r_nodes.iter().enumerate() {
+                    let copy = CopySource::new_bits(l_nodes[0].0, range.lo + idx as u8, 1);
+                    sim.set_node_src(*addr, Source::new_copy(copy));
+                }
+            }
+            BuiltinData::Merger(range) => {
+                let copy = CopySource::new_gather(l_nodes[0].0, range.count(), range.lo);
+                sim.set_node_src(r_nodes[0].0, Source::new_copy(copy));
+            }
+            _ => {}
+        }
+
+        if let Some(value) = ty.constant_value(&data) {
+            for (idx, (addr, ..)) in r_nodes.iter().enumerate() {
+                let bit = value.checked_shr(idx as u32).unwrap_or(0) & 1;
+                sim.mut_node(*addr).set_state(bit as u8);
+            }
+        }
+
+        Self {
+            ty,
+            data,
+            region,
+            pos,
+            rotation: Default::default(),
+            mirror: Default::default(),
+            l_nodes,
+            r_nodes,
+        }
+    }
+
     #[inline(always)]
     pub fn size(&self) -> Vec2 {
-        self.ty.size()
+        let size = match &self.data {
+            BuiltinData::PixelDisplay(cfg) => {
+                let pixels = vec2(cfg.width as f32, cfg.height as f32) * PIXEL_SIZE;
+                pixels + vec2(UNIT * 2.0, UNIT)
+            }
+            _ => self.ty.size(),
+        };
+        size.max(PinLayout::default().min_size([&self.l_nodes, &self.r_nodes]))
     }
 
     fn node_info(&self, side: Side, idx: u32) -> Option<NodeInfo> {
-        let (x, nodes) = match side {
-            Side::Left => (self.pos.x - CHIP_W * 0.5, &self.l_nodes),
-            Side::Right => (self.pos.x + CHIP_W * 0.5, &self.r_nodes),
+        let nodes = match side {
+            Side::Left => &self.l_nodes,
+            Side::Right => &self.r_nodes,
         };
-        let size = self.size();
-        let y = self.pos.y - size.y * 0.5 + (idx as f32) * UNIT + UNIT * 0.5;
-
-        let pos = vec2(x, y);
         let addr = nodes.get(idx as usize)?.0;
-        Some(NodeInfo { pos, addr })
+        let pins = [&self.l_nodes[..], &self.r_nodes[..]];
+        let (pos, edge) = PinLayout::default().pin_pos(self.bounds(), self.mirror, side, idx, pins);
+        Some(NodeInfo { pos, addr, edge })
     }
 
     pub fn bounds(&self) -> Rect {
         let size = self.size();
-        Rect::from_center_size(
-            egui::pos2(self.pos.x, self.pos.y),
-            egui::vec2(size.x, size.y),
-        )
+        Rect::from_center_size(self.pos, size)
     }
 
     fn sim_nodes(&self) -> Vec<NodeAddr> {
@@ -237,6 +1328,7 @@ pub struct Chip {
     pub region: NodeRegion,
     pub pos: Vec2,
     pub rotation: Rotation,
+    pub mirror: Mirror,
     pub save: Option<usize>,
     pub l_nodes: Vec<(NodeAddr, String, save::IoType)>,
     pub r_nodes: Vec<(NodeAddr, String, save::IoType)>,
@@ -244,16 +1336,17 @@ pub struct Chip {
 }
 impl Chip {
     fn node_info(&self, side: Side, idx: u32) -> Option<NodeInfo> {
-        let (x, nodes) = match side {
-            Side::Left => (self.pos.x - CHIP_W * 0.5, &self.l_nodes),
-            Side::Right => (self.pos.x + CHIP_W * 0.5, &self.r_nodes),
+        let nodes = match side {
+            Side::Left => &self.l_nodes,
+            Side::Right => &self.r_nodes,
         };
-        let size = self.size();
-        let y = self.pos.y - size.y * 0.5 + (idx as f32) * UNIT + UNIT * 0.5;
-
-        let pos = vec2(x, y);
         let addr = nodes.get(idx as usize)?.0;
-        Some(NodeInfo { pos, addr })
+        let pins = [&self.l_nodes[..], &self.r_nodes[..]];
+        let (pos, edge) =
+            self.attrs
+                .pin_layout
+                .pin_pos(self.bounds(), self.mirror, side, idx, pins);
+        Some(NodeInfo { pos, addr, edge })
     }
 
     fn sim_nodes(&self) -> Vec<NodeAddr> {
@@ -272,17 +1365,68 @@ impl Chip {
     }
 
     pub fn size(&self) -> Vec2 {
-        let max_nodes = self.l_nodes.len().max(self.r_nodes.len()) as f32;
-        vec2(CHIP_W, max_nodes * UNIT)
+        let min = (self.attrs.pin_layout).min_size([&self.l_nodes, &self.r_nodes]);
+        vec2(min.x.max(CHIP_W), min.y.max(UNIT))
     }
 
     pub fn bounds(&self) -> Rect {
         let size = self.size();
-        Rect::from_center_size(
-            egui::pos2(self.pos.x, self.pos.y),
-            egui::vec2(size.x, size.y),
-        )
+        Rect::from_center_size(self.pos, size)
+    }
+}
+
+/// Places a chip from the library in a scene, allocating it's nodes in the scene's simulation.
+pub fn place_chip(
+    scene: &mut Scene,
+    save_id: Option<usize>,
+    save: &save::ChipSave,
+    pos: Vec2,
+    rotation: Rotation,
+    mirror: Mirror,
+) -> SceneId {
+    let mut l_nodes = vec![];
+    let mut r_nodes = vec![];
+    let mut inner_nodes = vec![];
+    let region = scene.sim.alloc_region(save.region_size);
+
+    fn io_ty(node: &Node) -> save::IoType {
+        match node.source().ty() {
+            SourceTy::NONE => save::IoType::Input,
+            _ => save::IoType::Output,
+        }
     }
+
+    for (name, addr, state) in &save.l_nodes {
+        let addr = region.map(*addr);
+        scene.sim.set_node(addr, region.map_node(*state));
+        l_nodes.push((addr, name.clone(), io_ty(state)));
+    }
+    for (name, addr, state) in &save.r_nodes {
+        let addr = region.map(*addr);
+        scene.sim.set_node(addr, region.map_node(*state));
+        r_nodes.push((addr, name.clone(), io_ty(state)));
+    }
+    for (addr, state) in &save.inner_nodes {
+        let addr = region.map(*addr);
+        scene.sim.set_node(addr, region.map_node(*state));
+        inner_nodes.push(addr);
+    }
+    for native in &save.natives {
+        scene.sim.natives.push(native.map(&region));
+    }
+
+    let chip = Chip {
+        attrs: save.attrs.clone(),
+        region,
+        pos,
+        rotation,
+        mirror,
+        save: save_id,
+        l_nodes,
+        r_nodes,
+        inner_nodes,
+    };
+    scene.add_device(chip)
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -294,6 +1438,10 @@ impl Device {
     pub fn name(&self) -> &str {
         match self {
             Self::Chip(chip) => &chip.attrs.name,
+            Self::Builtin(BuiltinDevice {
+                data: BuiltinData::Tunnel(name),
+                ..
+            }) => name,
             Self::Builtin(builtin) => builtin.ty.name(),
         }
     }
@@ -325,6 +1473,26 @@ impl Device {
         }
     }
 
+    pub fn region(&self) -> &NodeRegion {
+        match self {
+            Self::Chip(x) => &x.region,
+            Self::Builtin(x) => &x.region,
+        }
+    }
+
+    pub fn mirror(&self) -> Mirror {
+        match self {
+            Self::Chip(x) => x.mirror,
+            Self::Builtin(x) => x.mirror,
+        }
+    }
+    pub fn mirror_mut(&mut self) -> &mut Mirror {
+        match self {
+            Self::Chip(x) => &mut x.mirror,
+            Self::Builtin(x) => &mut x.mirror,
+        }
+    }
+
     pub fn bounds(&self) -> Rect {
         match self {
             Self::Chip(x) => x.bounds(),
@@ -0,0 +1,98 @@
+//! Copies of the state of a simulation that it can be rewound to.
+
+use crate::sim::native::NativeDevice;
+use crate::sim::{Node, Sim};
+use serde::{Deserialize, Serialize};
+
+use std::collections::VecDeque;
+
+/// The state of every node and native device at one tick.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub tick: u64,
+    pub nodes: Vec<Node>,
+    pub natives: Vec<NativeDevice>,
+}
+impl Snapshot {
+    pub fn new(sim: &Sim) -> Self {
+        Self {
+            tick: sim.tick,
+            nodes: sim.nodes.clone(),
+            natives: sim.natives.clone(),
+        }
+    }
+
+    /// Sets the states of `sim` back to the snapshot.
+    /// Only the states are restored, so wires and devices that changed since are kept,
+    /// and nodes or devices that didn't exist yet are left as is.
+    pub fn restore(&self, sim: &mut Sim) {
+        for (node, saved) in sim.nodes.iter_mut().zip(&self.nodes) {
+            node.set_state(saved.state());
+        }
+        for native in &mut sim.natives {
+            let saved = self
+                .natives
+                .iter()
+                .find(|saved| saved.base() == native.base());
+            if let Some(saved) = saved {
+                *native = saved.clone();
+            }
+        }
+        sim.tick = self.tick;
+        // Recorded samples after the snapshot no longer happened
+        sim.recorder.clear();
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NamedSnapshot {
+    pub name: String,
+    pub snapshot: Snapshot,
+}
+
+/// Snapshots taken while the simulation runs, that can be scrubbed through.
+#[derive(Clone)]
+pub struct Timeline {
+    pub snapshots: VecDeque<Snapshot>,
+    /// The number of ticks between periodic snapshots, 0 disables them.
+    pub interval: u64,
+    /// The number of snapshots kept before the oldest are removed.
+    pub capacity: usize,
+}
+impl Default for Timeline {
+    fn default() -> Self {
+        Self {
+            snapshots: VecDeque::new(),
+            interval: 100,
+            capacity: 200,
+        }
+    }
+}
+impl Timeline {
+    /// Takes a snapshot if one is due.
+    pub fn update(&mut self, sim: &Sim) {
+        if self.interval != 0 && sim.tick.is_multiple_of(self.interval) {
+            self.push(Snapshot::new(sim));
+        }
+    }
+
+    /// Removes the snapshots after `tick`, when the simulation resumes from an earlier tick.
+    pub fn truncate(&mut self, tick: u64) {
+        self.snapshots.retain(|snapshot| snapshot.tick <= tick);
+    }
+
+    pub fn push(&mut self, snapshot: Snapshot) {
+        // Rewinding and resuming starts a new history
+        while self
+            .snapshots
+            .back()
+            .is_some_and(|last| last.tick >= snapshot.tick)
+        {
+            self.snapshots.pop_back();
+        }
+        self.snapshots.push_back(snapshot);
+        while self.snapshots.len() > self.capacity.max(1) {
+            self.snapshots.pop_front();
+        }
+    }
+}
@@ -0,0 +1,334 @@
+//! Test vectors, that check the outputs of a chip for given inputs.
+//!
+//! As text, each vector is a line of input values, the number of ticks to run and the expected
+//! output values, separated by `|`, e.g. `1 0 | 0 | 1`. Values are decimal, `0x` hex or `0b`
+//! binary, and `x` is a don't-care. An input that's a don't-care keeps it's previous value,
+//! and 0 ticks runs until the chip settles. Lines starting with `#` are comments, and the
+//! first line can be a header of pin names.
+
+use crate::sim::bench::TestBench;
+use crate::sim::save::ChipSave;
+use crate::sim::TruthTable;
+use serde::{Deserialize, Serialize};
+
+/// The most ticks a vector with 0 ticks waits for the chip to settle.
+pub const SETTLE_TICKS: u32 = 1000;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TestVector {
+    /// The value of each input, None keeps the previous value.
+    pub inputs: Vec<Option<u8>>,
+    /// The number of ticks to run after setting the inputs, 0 runs until the chip settles.
+    pub ticks: u32,
+    /// The expected value of each output, None is a don't-care.
+    pub outputs: Vec<Option<u8>>,
+}
+
+/// Parses a decimal, `0x` hex or `0b` binary value, or `x` for a don't-care.
+pub fn parse_value(text: &str) -> Result<Option<u8>, String> {
+    let parsed = match text {
+        "x" | "X" | "-" => return Ok(None),
+        _ if text.starts_with("0x") => u8::from_str_radix(&text[2..], 16),
+        _ if text.starts_with("0b") => u8::from_str_radix(&text[2..], 2),
+        _ => text.parse(),
+    };
+    parsed
+        .map(Some)
+        .map_err(|_| format!("{text:?} isn't a value from 0 to 255 or x"))
+}
+
+fn format_value(value: Option<u8>) -> String {
+    match value {
+        Some(value) => format!("{value}"),
+        None => String::from("x"),
+    }
+}
+
+/// Checks that `value` fits in an input `width` bits wide, see [TestBench::input_widths].
+pub fn check_width(name: &str, value: u8, width: u8) -> Result<(), String> {
+    match value.checked_shr(width as u32).unwrap_or(0) {
+        0 => Ok(()),
+        _ => Err(format!(
+            "{value} doesn't fit in {name}, which is {width} bits wide"
+        )),
+    }
+}
+
+/// Parses the vectors of a chip with the given input and output names, and the number of bits each
+/// input is wide.
+pub fn parse(
+    text: &str,
+    inputs: &[String],
+    outputs: &[String],
+    widths: &[u8],
+) -> Result<Vec<TestVector>, String> {
+    let mut vectors = vec![];
+    let lines = (text.lines().enumerate())
+        .map(|(idx, line)| (idx + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+    for (line_idx, line) in lines {
+        let columns: Vec<Vec<&str>> = (line.split('|'))
+            .map(|column| column.split_whitespace().collect())
+            .collect();
+        let [in_values, ticks, out_values] = &columns[..] else {
+            return Err(format!(
+                "line {line_idx}: expected `inputs | ticks | outputs`"
+            ));
+        };
+
+        // A header names the pins, which have to be in the same order as the chip's
+        let header = (in_values.iter().chain(out_values)).any(|value| parse_value(value).is_err());
+        if header && vectors.is_empty() {
+            if in_values[..] != inputs[..] || out_values[..] != outputs[..] {
+                return Err(format!(
+                    "line {line_idx}: the header doesn't match the pins `{} | ticks | {}`",
+                    inputs.join(" "),
+                    outputs.join(" ")
+                ));
+            }
+            continue;
+        }
+
+        let values = |list: &[&str], expected: usize, what: &str| {
+            if list.len() != expected {
+                return Err(format!(
+                    "line {line_idx}: {} {what} values instead of {expected}",
+                    list.len()
+                ));
+            }
+            (list.iter())
+                .map(|value| parse_value(value).map_err(|err| format!("line {line_idx}: {err}")))
+                .collect::<Result<Vec<_>, String>>()
+        };
+        let ticks = match ticks[..] {
+            [] => 0,
+            [ticks] => ticks
+                .parse()
+                .map_err(|_| format!("line {line_idx}: {ticks:?} isn't a number of ticks"))?,
+            _ => return Err(format!("line {line_idx}: expected one number of ticks")),
+        };
+        let vector = TestVector {
+            inputs: values(in_values, inputs.len(), "input")?,
+            ticks,
+            outputs: values(out_values, outputs.len(), "output")?,
+        };
+        for ((name, value), width) in inputs.iter().zip(&vector.inputs).zip(widths) {
+            if let Some(value) = value {
+                check_width(name, *value, *width)
+                    .map_err(|err| format!("line {line_idx}: {err}"))?;
+            }
+        }
+        vectors.push(vector);
+    }
+    Ok(vectors)
+}
+
+/// Formats vectors as text that [parse] reads, with a header of pin names.
+pub fn format(vectors: &[TestVector], inputs: &[String], outputs: &[String]) -> String {
+    let mut out = format!("{} | ticks | {}\n", inputs.join(" "), outputs.join(" "));
+    for vector in vectors {
+        let values = |list: &[Option<u8>]| {
+            (list.iter())
+                .map(|value| format_value(*value))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        out.push_str(&format!(
+            "{} | {} | {}\n",
+            values(&vector.inputs),
+            vector.ticks,
+            values(&vector.outputs)
+        ));
+    }
+    out
+}
+
+/// The vector a chip failed on.
+#[derive(Clone, Debug)]
+pub struct TestFailure {
+    /// The index of the vector.
+    pub row: usize,
+    /// The outputs after the vector ran.
+    pub actual: Vec<u8>,
+    pub reason: String,
+}
+impl TestFailure {
+    pub fn message(&self, vector: &TestVector) -> String {
+        let values = |list: Vec<String>| list.join(" ");
+        format!(
+            "row {}: {}, expected {} got {}",
+            self.row + 1,
+            self.reason,
+            values(vector.outputs.iter().map(|v| format_value(*v)).collect()),
+            values(self.actual.iter().map(|v| format!("{v}")).collect()),
+        )
+    }
+}
+
+/// Runs the vectors one after another on a chip placed in a new simulation.
+/// Returns the number of vectors that ran, or the first one that failed.
+pub fn run(
+    save: &ChipSave,
+    tables: &[TruthTable],
+    vectors: &[TestVector],
+) -> Result<usize, TestFailure> {
+    let mut bench = TestBench::from_chip(save);
+    let widths = bench.input_widths(tables);
+    for (row, vector) in vectors.iter().enumerate() {
+        let fail = |bench: &TestBench, reason: String| TestFailure {
+            row,
+            actual: (0..bench.outputs.len())
+                .map(|idx| bench.output(idx))
+                .collect(),
+            reason,
+        };
+        if vector.inputs.len() != bench.inputs.len() || vector.outputs.len() != bench.outputs.len()
+        {
+            let reason = format!(
+                "the vector has {} inputs and {} outputs, the chip has {} and {}",
+                vector.inputs.len(),
+                vector.outputs.len(),
+                bench.inputs.len(),
+                bench.outputs.len()
+            );
+            return Err(fail(&bench, reason));
+        }
+
+        for (idx, value) in vector.inputs.iter().enumerate() {
+            if let Some(value) = value {
+                if let Err(reason) = check_width(&bench.inputs[idx].0, *value, widths[idx]) {
+                    return Err(fail(&bench, reason));
+                }
+                bench.set_input(idx, *value);
+            }
+        }
+        match vector.ticks {
+            0 => {
+                if bench.settle(tables, SETTLE_TICKS).is_none() {
+                    let reason = format!("didn't settle within {SETTLE_TICKS} ticks");
+                    return Err(fail(&bench, reason));
+                }
+            }
+            ticks => (0..ticks).for_each(|_| bench.sim.update(tables)),
+        }
+
+        let wrong: Vec<&str> = (vector.outputs.iter().enumerate())
+            .filter(|(idx, expected)| expected.is_some_and(|value| value != bench.output(*idx)))
+            .map(|(idx, _)| bench.outputs[idx].0.as_str())
+            .collect();
+        if !wrong.is_empty() {
+            return Err(fail(&bench, format!("wrong {}", wrong.join(", "))));
+        }
+    }
+    Ok(vectors.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::save::{Library, StartingChip};
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn and_chip() -> (Library, Vec<String>, Vec<String>) {
+        let mut library = Library::default();
+        StartingChip::And.create(&mut library);
+        let chip = &library.chips[0];
+        let inputs = chip.l_nodes.iter().map(|(name, ..)| name.clone()).collect();
+        let outputs = chip.r_nodes.iter().map(|(name, ..)| name.clone()).collect();
+        (library, inputs, outputs)
+    }
+
+    #[test]
+    fn values() {
+        assert_eq!(parse_value("12"), Ok(Some(12)));
+        assert_eq!(parse_value("0x1f"), Ok(Some(31)));
+        assert_eq!(parse_value("0b101"), Ok(Some(5)));
+        assert_eq!(parse_value("x"), Ok(None));
+        assert!(parse_value("256").is_err());
+        assert!(parse_value("y").is_err());
+    }
+
+    #[test]
+    fn format_round_trip() {
+        let (inputs, outputs) = (names(&["a", "b"]), names(&["q"]));
+        let text = "# comment\n1 x | 3 | 0\n\n0 1 | | x\n";
+        let vectors = parse(text, &inputs, &outputs, &[1, 1]).unwrap();
+        assert_eq!(
+            vectors,
+            [
+                TestVector {
+                    inputs: vec![Some(1), None],
+                    ticks: 3,
+                    outputs: vec![Some(0)],
+                },
+                TestVector {
+                    inputs: vec![Some(0), Some(1)],
+                    ticks: 0,
+                    outputs: vec![None],
+                },
+            ]
+        );
+        let text = format(&vectors, &inputs, &outputs);
+        assert_eq!(parse(&text, &inputs, &outputs, &[1, 1]), Ok(vectors));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let (inputs, outputs) = (names(&["a", "b"]), names(&["q"]));
+        let err = |text: &str| parse(text, &inputs, &outputs, &[1, 1]).unwrap_err();
+        assert_eq!(
+            err("1 1 | 1"),
+            "line 1: expected `inputs | ticks | outputs`"
+        );
+        assert_eq!(err("1 | 1 | 1"), "line 1: 1 input values instead of 2");
+        assert_eq!(err("1 1 | y | 1"), "line 1: \"y\" isn't a number of ticks");
+        assert_eq!(
+            err("b a | ticks | q"),
+            "line 1: the header doesn't match the pins `a b | ticks | q`"
+        );
+        assert_eq!(
+            err("a b | ticks | q\n2 1 | 1 | 1"),
+            "line 2: 2 doesn't fit in a, which is 1 bits wide"
+        );
+    }
+
+    #[test]
+    fn runs_vectors() {
+        let (library, inputs, outputs) = and_chip();
+        let chip = &library.chips[0];
+        let widths = TestBench::from_chip(chip).input_widths(&library.tables);
+        assert_eq!(widths, [1, 1]);
+
+        let vectors = parse(
+            "0 0 | | 0\n1 0 | | 0\nx 1 | | 1",
+            &inputs,
+            &outputs,
+            &widths,
+        );
+        assert_eq!(run(chip, &library.tables, &vectors.unwrap()).ok(), Some(3));
+
+        let vectors = parse("1 1 | | 0", &inputs, &outputs, &widths).unwrap();
+        let failure = run(chip, &library.tables, &vectors).unwrap_err();
+        assert_eq!((failure.row, failure.actual), (0, vec![1]));
+    }
+
+    #[test]
+    fn run_rejects_wide_values() {
+        let (library, ..) = and_chip();
+        let vectors = [TestVector {
+            inputs: vec![Some(255), Some(1)],
+            ticks: 1,
+            outputs: vec![None],
+        }];
+        let failure = run(&library.chips[0], &library.tables, &vectors).unwrap_err();
+        assert_eq!(failure.row, 0);
+        assert!(
+            failure.reason.contains("255 doesn't fit"),
+            "{}",
+            failure.reason
+        );
+    }
+}
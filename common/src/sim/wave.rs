@@ -0,0 +1,261 @@
+//! Recording of node states over time, and exporting them as a Value Change Dump.
+
+use crate::sim::{Node, NodeAddr};
+use serde::{Deserialize, Serialize};
+
+use std::collections::VecDeque;
+
+/// A node that can be recorded.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Signal {
+    pub name: String,
+    /// The names of the scene and chips the node is in, outermost first.
+    pub scope: Vec<String>,
+    pub addr: NodeAddr,
+    /// The number of bits of the node's state that are recorded.
+    pub width: u8,
+}
+
+/// Records the states of the selected signals every tick into a ring buffer.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Recorder {
+    pub signals: Vec<Signal>,
+    pub enabled: bool,
+    /// The number of ticks that are kept before the oldest are removed.
+    pub capacity: usize,
+    /// The state of every signal, tick by tick.
+    #[serde(skip)]
+    samples: VecDeque<u8>,
+    /// The tick of the oldest sample.
+    #[serde(skip)]
+    first_tick: u64,
+}
+impl Default for Recorder {
+    fn default() -> Self {
+        Self {
+            signals: vec![],
+            enabled: false,
+            capacity: 10_000,
+            samples: VecDeque::new(),
+            first_tick: 0,
+        }
+    }
+}
+impl Recorder {
+    /// Adds a signal, if it's node isn't already recorded. This clears the recorded samples.
+    pub fn add_signal(&mut self, signal: Signal) {
+        if !self.is_recorded(signal.addr) {
+            self.signals.push(signal);
+            self.clear();
+        }
+    }
+
+    /// Removes the signal recording `addr`. This clears the recorded samples.
+    pub fn remove_signal(&mut self, addr: NodeAddr) {
+        self.signals.retain(|signal| signal.addr != addr);
+        self.clear();
+    }
+
+    pub fn is_recorded(&self, addr: NodeAddr) -> bool {
+        self.signals.iter().any(|signal| signal.addr == addr)
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// The number of ticks that are recorded.
+    pub fn len(&self) -> usize {
+        match self.signals.len() {
+            0 => 0,
+            n => self.samples.len() / n,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The tick of the oldest recorded sample.
+    pub fn first_tick(&self) -> u64 {
+        self.first_tick
+    }
+
+    /// The state of `signal` at the `idx`th recorded tick.
+    pub fn sample(&self, idx: usize, signal: usize) -> u8 {
+        self.samples[idx * self.signals.len() + signal]
+    }
+
+    pub fn record(&mut self, tick: u64, nodes: &[Node]) {
+        if !self.enabled || self.signals.is_empty() {
+            return;
+        }
+        if self.samples.is_empty() {
+            self.first_tick = tick;
+        }
+        for signal in &self.signals {
+            let state = nodes.get(signal.addr.0 as usize).map_or(0, Node::state);
+            self.samples.push_back(state & mask(signal.width));
+        }
+        while self.len() > self.capacity.max(1) {
+            self.samples.drain(..self.signals.len());
+            self.first_tick += 1;
+        }
+    }
+
+    /// Writes the recorded samples as a Value Change Dump, with one tick per nanosecond.
+    pub fn to_vcd(&self) -> String {
+        let mut out = String::new();
+        out.push_str("$version mlsim $end\n");
+        out.push_str("$timescale 1ns $end\n");
+
+        // Signals in the same scope have to be declared together
+        let mut order: Vec<usize> = (0..self.signals.len()).collect();
+        order.sort_by(|a, b| self.signals[*a].scope.cmp(&self.signals[*b].scope));
+        let mut current: Vec<&str> = vec![];
+        for idx in order {
+            let signal = &self.signals[idx];
+            let common = (current.iter().zip(&signal.scope))
+                .take_while(|(a, b)| **a == b.as_str())
+                .count();
+            for _ in common..current.len() {
+                out.push_str("$upscope $end\n");
+            }
+            current.truncate(common);
+            for name in &signal.scope[common..] {
+                out.push_str(&format!("$scope module {} $end\n", identifier(name)));
+                current.push(name);
+            }
+            out.push_str(&format!(
+                "$var wire {} {} {} $end\n",
+                signal.width.max(1),
+                id_code(idx),
+                identifier(&signal.name)
+            ));
+        }
+        for _ in current {
+            out.push_str("$upscope $end\n");
+        }
+        out.push_str("$enddefinitions $end\n");
+
+        for tick in 0..self.len() {
+            let changed: Vec<usize> = (0..self.signals.len())
+                .filter(|signal| {
+                    tick == 0 || self.sample(tick, *signal) != self.sample(tick - 1, *signal)
+                })
+                .collect();
+            if changed.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("#{}\n", self.first_tick + tick as u64));
+            if tick == 0 {
+                out.push_str("$dumpvars\n");
+            }
+            for signal in changed {
+                let state = self.sample(tick, signal);
+                let value = match self.signals[signal].width {
+                    0 | 1 => format!("{}", state & 1),
+                    _ => format!("b{state:b} "),
+                };
+                out.push_str(&format!("{value}{}\n", id_code(signal)));
+            }
+            if tick == 0 {
+                out.push_str("$end\n");
+            }
+        }
+        out.push_str(&format!("#{}\n", self.first_tick + self.len() as u64));
+        out
+    }
+}
+
+fn mask(width: u8) -> u8 {
+    0xFFu8 >> (8 - width.clamp(1, 8))
+}
+
+/// The short code a signal is referred to by in the value changes of a dump.
+fn id_code(mut idx: usize) -> String {
+    const FIRST: u8 = b'!';
+    const COUNT: usize = (b'~' - b'!') as usize + 1;
+    let mut out = String::new();
+    loop {
+        out.push((FIRST + (idx % COUNT) as u8) as char);
+        idx /= COUNT;
+        if idx == 0 {
+            break out;
+        }
+        idx -= 1;
+    }
+}
+
+/// Replaces the characters of `name` that can't be in an identifier.
+fn identifier(name: &str) -> String {
+    let out: String = name
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c,
+            false => '_',
+        })
+        .collect();
+    match out.is_empty() {
+        true => String::from("_"),
+        false => out,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nodes(states: [u8; 2]) -> Vec<Node> {
+        (states.into_iter())
+            .map(|state| {
+                let mut node = Node::default();
+                node.set_state(state);
+                node
+            })
+            .collect()
+    }
+
+    #[test]
+    fn vcd() {
+        let signal = |name: &str, scope: &[&str], addr: u32, width: u8| Signal {
+            name: String::from(name),
+            scope: scope.iter().map(|s| s.to_string()).collect(),
+            addr: NodeAddr(addr),
+            width,
+        };
+        let mut recorder = Recorder {
+            enabled: true,
+            ..Default::default()
+        };
+        recorder.add_signal(signal("sum", &["Main", "Adder"], 1, 4));
+        recorder.add_signal(signal("clk", &["Main"], 0, 1));
+        recorder.record(5, &nodes([0, 3]));
+        recorder.record(6, &nodes([1, 3]));
+        recorder.record(7, &nodes([1, 3]));
+        recorder.record(8, &nodes([1, 10]));
+
+        let expected = "\
+$version mlsim $end
+$timescale 1ns $end
+$scope module Main $end
+$var wire 1 \" clk $end
+$scope module Adder $end
+$var wire 4 ! sum $end
+$upscope $end
+$upscope $end
+$enddefinitions $end
+#5
+$dumpvars
+b11 !
+0\"
+$end
+#6
+1\"
+#8
+b1010 !
+#9
+";
+        assert_eq!(recorder.to_vcd(), expected);
+    }
+}
@@ -0,0 +1,79 @@
+use crate::sim::expr::Program;
+use crate::sim::save::Library;
+
+use egui::Ui;
+
+/// Creates a chip from boolean expressions.
+pub struct ExprEditor {
+    name: String,
+    category: String,
+    text: String,
+    /// If the chip is made of gates from the library, instead of a truth table.
+    gates: bool,
+    status: Result<String, String>,
+}
+impl Default for ExprEditor {
+    fn default() -> Self {
+        Self {
+            name: String::from("New Chip"),
+            category: String::from("Basic"),
+            text: String::from("out = (a & b) | !c"),
+            gates: false,
+            status: Ok(String::new()),
+        }
+    }
+}
+impl ExprEditor {
+    pub fn show(&mut self, ui: &mut Ui, library: &mut Library) {
+        ui.heading("New Chip From Expressions");
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("Name: ");
+            ui.text_edit_singleline(&mut self.name);
+        });
+        ui.horizontal(|ui| {
+            ui.label("Category: ");
+            ui.text_edit_singleline(&mut self.category);
+        });
+        ui.add(
+            egui::TextEdit::multiline(&mut self.text)
+                .code_editor()
+                .desired_rows(6)
+                .hint_text("sum = a ^ b ^ cin\ncout = (a & b) | (cin & (a ^ b))"),
+        );
+        ui.small("operators: ! (not), & (and), ^ (xor), | (or), and parentheses");
+
+        let program = Program::parse(&self.text);
+        match &program {
+            Ok(program) => {
+                ui.label(format!("inputs: {}", program.inputs.join(", ")));
+                let outputs: Vec<&str> = program.outputs.iter().map(|(s, _)| s.as_str()).collect();
+                ui.label(format!("outputs: {}", outputs.join(", ")));
+            }
+            Err(err) => {
+                ui.colored_label(ui.visuals().error_fg_color, err);
+            }
+        }
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.gates, false, "Truth table");
+            ui.radio_value(&mut self.gates, true, "Gates");
+        });
+        if self.gates {
+            ui.small("uses the And, Or, Not and Xor chips of the library");
+        }
+        let rs = ui.add_enabled(program.is_ok(), egui::Button::new("Add to library"));
+        if let (true, Ok(program)) = (rs.clicked(), &program) {
+            let result = match self.gates {
+                true => program.create_gate_chip(library, &self.name, &self.category),
+                false => program.create_table_chip(library, &self.name, &self.category),
+            };
+            self.status = result.map(|_| format!("added {}", self.name));
+        }
+        match &self.status {
+            Ok(msg) => ui.label(msg),
+            Err(err) => ui.colored_label(ui.visuals().error_fg_color, err),
+        };
+    }
+}
@@ -1,6 +1,17 @@
-use crate::save::{create_chip_from_scene, IoType, Project, StartingChip};
-use crate::sim::scene::{BuiltinDeviceTy, NodeIdent, Scene, Wire, UNIT};
-use crate::sim::{NodeAddr, Source};
+use crate::save::{create_chip_from_scene, ChipSave, IoType, Project, StartingChip};
+use crate::sim::debug::{Breakpoint, Condition};
+use crate::sim::ihex;
+use crate::sim::native::{MemoryConfig, NativeDevice, Ram, Rom};
+use crate::sim::scene::{
+    place_chip, BitRange, BuiltinData, BuiltinDeviceTy, Device, Junction, Key, NodeIdent, Scene,
+    SceneId, Side, Wire, UNIT,
+};
+use crate::sim::snapshot::{NamedSnapshot, Snapshot};
+use crate::sim::NodeAddr;
+use crate::ui::expr::ExprEditor;
+use crate::ui::truth::{TableEditor, TruthTableViewer};
+use crate::ui::vector::TestEditor;
+use crate::ui::wave::WaveformViewer;
 use crate::{Platform, Settings};
 
 use egui::Ui;
@@ -12,6 +23,9 @@ pub struct PageOutput<P> {
     pub update_settings: Option<Settings>,
     pub clicked_node: Option<(NodeIdent, NodeAddr, IoType)>,
     pub rclicked_node: Option<(NodeIdent, NodeAddr, IoType)>,
+    pub clicked_device: Option<SceneId>,
+    /// The input and output of the clicked wire, and the segment and point on it that was clicked.
+    pub clicked_wire: Option<((NodeIdent, NodeIdent), usize, Vec2)>,
 }
 impl<P> Default for PageOutput<P> {
     fn default() -> Self {
@@ -21,6 +35,8 @@ impl<P> Default for PageOutput<P> {
             update_settings: None,
             clicked_node: None,
             rclicked_node: None,
+            clicked_device: None,
+            clicked_wire: None,
         }
     }
 }
@@ -137,7 +153,8 @@ impl<P: Platform> Page<P> for ProjectSelectPage {
                     if ui.button("open").clicked() {
                         match P::load_project(project) {
                             Err(err) => self.load_err = Some(err),
-                            Ok(project) => {
+                            Ok(mut project) => {
+                                project.on_load();
                                 out.pop_page = true;
                                 out.push_page(WorkspacePage::new(project));
                             }
@@ -348,6 +365,14 @@ impl WorkspaceMenu {
                     page.show_grid = !page.show_grid;
                 }
 
+                let label = match page.auto_route {
+                    true => "Auto route: On",
+                    false => "Auto route: Off",
+                };
+                if button(ui, label).clicked() {
+                    page.auto_route = !page.auto_route;
+                }
+
                 if button(ui, "Settings").clicked() {
                     out.push_page(SettingsPage(settings.clone()));
                 }
@@ -370,8 +395,45 @@ impl WorkspaceMenu {
                         scene.save_attrs.logic.cycle_in_place();
                     }
                 });
+                ui.separator();
+                ui.label("Pin layout");
+                egui::Grid::new("pin-layout").show(ui, |ui| {
+                    let layout = &mut scene.save_attrs.pin_layout;
+                    for (side, nodes) in [
+                        (Side::Left, &scene.l_nodes.states),
+                        (Side::Right, &scene.r_nodes.states),
+                    ] {
+                        for (idx, (_addr, name)) in nodes.iter().enumerate() {
+                            let mut pin = layout.get(side, name, idx as u32);
+                            ui.label(name);
+                            if ui.button(format!("{:?}", pin.edge)).clicked() {
+                                pin.edge = pin.edge.next();
+                            }
+                            ui.add(egui::DragValue::new(&mut pin.order).prefix("order: "));
+                            ui.end_row();
+
+                            if pin != layout.get(side, name, idx as u32) {
+                                layout.set(side, name, pin);
+                            }
+                        }
+                    }
+                });
+                // Pins are placed by their name, so two pins on a side can't share one
+                let duplicate = [&scene.l_nodes.states, &scene.r_nodes.states]
+                    .into_iter()
+                    .find_map(|nodes| {
+                        (nodes.iter().enumerate())
+                            .find(|(idx, (_, name))| nodes[..*idx].iter().any(|(_, n)| n == name))
+                            .map(|(_, (_, name))| name.clone())
+                    });
+                if let Some(name) = &duplicate {
+                    let err = format!("More than one pin on a side is named {name:?}");
+                    ui.colored_label(ui.visuals().error_fg_color, err);
+                }
+                ui.separator();
                 ui.horizontal(|ui| {
-                    if ui.button("Create").clicked() {
+                    let rs = ui.add_enabled(duplicate.is_none(), egui::Button::new("Create"));
+                    if rs.clicked() {
                         // self.scene.optimize();
                         let save = create_chip_from_scene(&page.project.scenes[page.open_scene]);
                         page.project.scenes.remove(page.open_scene);
@@ -384,7 +446,9 @@ impl WorkspaceMenu {
                             .iter_mut()
                             .find(|chip| chip.attrs.name == save.attrs.name)
                         {
-                            *c = save;
+                            // Repacking a chip keeps it's tests
+                            let tests = std::mem::take(&mut c.tests);
+                            *c = ChipSave { tests, ..save };
                         } else {
                             page.project.library.chips.push(save);
                         }
@@ -426,12 +490,15 @@ impl Default for DeviceCursor {
 pub struct WirePlacement {
     src: (NodeIdent, NodeAddr),
     anchors: Vec<Vec2>,
+    /// Where on an existing wire this wire branches off of.
+    junction: Option<Junction>,
 }
 
 pub struct WorkspacePage {
     pub project: Project,
     pub snap_to_grid: bool,
     pub show_grid: bool,
+    pub auto_route: bool,
     pub open_scene: usize,
     pub open_menu: Option<WorkspaceMenu>,
     pub items: Vec<(String, Vec<PlaceDevice>, bool)>,
@@ -439,6 +506,28 @@ pub struct WorkspacePage {
 
     pub cursor: DeviceCursor,
     pub wire_placement: Option<WirePlacement>,
+    pub selected_device: Option<SceneId>,
+    pub show_nets: bool,
+    /// If the next key pressed should be bound to the selected button.
+    pub binding_key: bool,
+    /// The path of the file to load into the selected memory device.
+    pub memory_path: String,
+    pub memory_error: Option<String>,
+    pub show_recorder: bool,
+    /// The path the recorded waveforms are exported to.
+    pub export_path: String,
+    pub export_status: Option<String>,
+    pub show_timeline: bool,
+    /// The name the next named snapshot is saved with.
+    pub snapshot_name: String,
+    pub show_debug: bool,
+    /// If the next clicked pin or wire gets a breakpoint.
+    pub picking_breakpoint: bool,
+    pub table_editor: Option<TableEditor>,
+    pub expr_editor: Option<ExprEditor>,
+    pub test_editor: Option<TestEditor>,
+    pub waveforms: Option<WaveformViewer>,
+    pub truth_table: Option<TruthTableViewer>,
 }
 impl WorkspacePage {
     pub fn new(project: Project) -> Self {
@@ -446,6 +535,7 @@ impl WorkspacePage {
             project,
             show_grid: true,
             snap_to_grid: true,
+            auto_route: false,
             open_scene: 0,
             open_menu: None,
             items: vec![],
@@ -453,6 +543,23 @@ impl WorkspacePage {
 
             cursor: DeviceCursor::default(),
             wire_placement: None,
+            selected_device: None,
+            show_nets: false,
+            binding_key: false,
+            memory_path: String::new(),
+            memory_error: None,
+            show_recorder: false,
+            export_path: String::from("waves.vcd"),
+            export_status: None,
+            show_timeline: false,
+            snapshot_name: String::new(),
+            show_debug: false,
+            picking_breakpoint: false,
+            table_editor: None,
+            expr_editor: None,
+            test_editor: None,
+            waveforms: None,
+            truth_table: None,
         }
     }
 }
@@ -489,11 +596,20 @@ impl WorkspacePage {
         let scene = &mut self.project.scenes[self.open_scene];
         let corner = self.cursor.corner;
         let center = self.cursor.pos;
-        let size = match device {
-            PlaceDevice::Builtin(builtin) => builtin.size(),
-            PlaceDevice::Chip(id) => self.project.library.chips[id]
-                .preview(center, Default::default())
-                .size(),
+        // Builtins are created first, as their size can depend on their data
+        let (size, builtin) = match device {
+            PlaceDevice::Builtin(ty) => {
+                let builtin = crate::sim::scene::BuiltinDevice::new(ty, &mut scene.sim, center);
+                (builtin.size(), Some(builtin))
+            }
+            PlaceDevice::Chip(id) => {
+                let preview = self.project.library.chips[id].preview(
+                    center,
+                    Default::default(),
+                    Default::default(),
+                );
+                (preview.size(), None)
+            }
         };
         self.cursor.pos.y += size.y;
         let center = match corner {
@@ -504,82 +620,13 @@ impl WorkspacePage {
         };
 
         log::info!("placing deivce: {device:?}");
-        match device {
-            PlaceDevice::Builtin(ty) => {
-                use crate::sim::{scene, Node};
-
-                let mut l_nodes = vec![];
-                let mut r_nodes = vec![];
-                let (input_count, output_count) = ty.io();
-                let region = scene
-                    .sim
-                    .alloc_region(input_count as u32 + output_count as u32);
-
-                for i in 0..input_count {
-                    let addr = region.map(i as u32);
-                    scene.sim.set_node(addr, Node::default());
-                    l_nodes.push((addr, format!("in{i}"), IoType::Input));
-                }
-                for i in 0..output_count {
-                    let addr = region.map(i as u32 + input_count as u32);
-                    scene.sim.set_node(addr, Node::default());
-                    r_nodes.push((addr, format!("out{i}"), IoType::Output));
-                }
-
-                let device = scene::BuiltinDevice {
-                    ty,
-                    region,
-                    pos: center,
-                    rotation: Default::default(),
-                    l_nodes,
-                    r_nodes,
-                };
-                scene.add_device(device);
-            }
-            PlaceDevice::Chip(id) => {
-                use crate::sim::{scene, Node, SourceTy};
-
-                let mut l_nodes = vec![];
-                let mut r_nodes = vec![];
-                let mut inner_nodes = vec![];
-                let save = &self.project.library.chips[id];
-                let region = scene.sim.alloc_region(save.region_size);
-
-                fn io_ty(node: &Node) -> IoType {
-                    match node.source().ty() {
-                        SourceTy::NONE => IoType::Input,
-                        _ => IoType::Output,
-                    }
-                }
-
-                for (name, addr, state) in &save.l_nodes {
-                    let addr = region.map(*addr);
-                    scene.sim.set_node(addr, region.map_node(*state));
-                    l_nodes.push((addr, name.clone(), io_ty(state)));
-                }
-                for (name, addr, state) in &save.r_nodes {
-                    let addr = region.map(*addr);
-                    scene.sim.set_node(addr, region.map_node(*state));
-                    r_nodes.push((addr, name.clone(), io_ty(state)));
-                }
-                for (addr, state) in &save.inner_nodes {
-                    let addr = region.map(*addr);
-                    scene.sim.set_node(addr, region.map_node(*state));
-                    inner_nodes.push(addr);
-                }
-
-                let chip = scene::Chip {
-                    attrs: save.attrs.clone(),
-                    region,
-                    pos: center,
-                    rotation: Default::default(),
-                    save: Some(id),
-                    l_nodes,
-                    r_nodes,
-                    inner_nodes,
-                };
-                scene.add_device(chip);
-            }
+        if let Some(mut builtin) = builtin {
+            builtin.pos = center;
+            scene.add_device(builtin);
+        } else if let PlaceDevice::Chip(id) = device {
+            let save = &self.project.library.chips[id];
+            let rotation = Default::default();
+            _ = place_chip(scene, Some(id), save, center, rotation, Default::default());
         }
     }
 
@@ -635,6 +682,463 @@ impl WorkspacePage {
         }
     }
 
+    fn show_device_panel<P: Platform>(&mut self, ui: &mut Ui) {
+        let scene = self.project.scenes.get_mut(self.open_scene);
+        let Some((device, sim)) = scene
+            .zip(self.selected_device)
+            .and_then(|(scene, id)| Some((scene.devices.get_mut(&id)?, &mut scene.sim)))
+        else {
+            self.selected_device = None;
+            return;
+        };
+        let mut reconfigure = None;
+        ui.heading(device.name());
+        ui.separator();
+        ui.horizontal(|ui| {
+            let mirror = device.mirror_mut();
+            ui.toggle_value(&mut mirror.h, "Mirror H");
+            ui.toggle_value(&mut mirror.v, "Mirror V");
+        });
+        if let Device::Builtin(builtin) = device {
+            match &mut builtin.data {
+                BuiltinData::None => {}
+                BuiltinData::Button { key } => {
+                    ui.horizontal(|ui| {
+                        ui.label("Key: ");
+                        let label = match (self.binding_key, *key) {
+                            (true, _) => String::from("press a key..."),
+                            (false, Some(key)) => match key.to_egui() {
+                                Some(key) => String::from(key.name()),
+                                None => String::from("unknown"),
+                            },
+                            (false, None) => String::from("none"),
+                        };
+                        let rs = ui.button(label);
+                        if rs.clicked() {
+                            self.binding_key = true;
+                        }
+                        if rs.secondary_clicked() {
+                            *key = None;
+                        }
+                    });
+                    if self.binding_key {
+                        let pressed = ui.input(|input| {
+                            input.events.iter().find_map(|event| match event {
+                                egui::Event::Key {
+                                    key, pressed: true, ..
+                                } => Some(*key),
+                                _ => None,
+                            })
+                        });
+                        if let Some(pressed) = pressed {
+                            *key = (pressed != egui::Key::Escape).then(|| Key::from_egui(pressed));
+                            self.binding_key = false;
+                        }
+                    }
+                }
+                BuiltinData::Tunnel(name) => {
+                    ui.horizontal(|ui| {
+                        ui.label("Net: ");
+                        ui.text_edit_singleline(name);
+                    });
+                }
+                BuiltinData::PixelDisplay(cfg) => {
+                    let mut new = *cfg;
+                    ui.add(egui::Slider::new(&mut new.width, 1..=64).text("width"));
+                    ui.add(egui::Slider::new(&mut new.height, 1..=64).text("height"));
+                    ui.checkbox(&mut new.strobe, "Write pin");
+                    ui.checkbox(&mut new.clear, "Clear pin");
+                    if new != *cfg {
+                        reconfigure = Some(BuiltinData::PixelDisplay(new));
+                    }
+                }
+                BuiltinData::Constant { bits, value } => {
+                    let (mut new_bits, mut new_value) = (*bits, *value);
+                    ui.add(egui::Slider::new(&mut new_bits, 1..=32).text("bits"));
+                    let mask = u32::MAX >> (32 - new_bits);
+                    ui.horizontal(|ui| {
+                        ui.label("Value: ");
+                        let digits = (new_bits as usize).div_ceil(4);
+                        ui.add(
+                            egui::DragValue::new(&mut new_value).hexadecimal(digits, false, true),
+                        );
+                    });
+                    new_value &= mask;
+                    if (new_bits, new_value) != (*bits, *value) {
+                        reconfigure = Some(BuiltinData::Constant {
+                            bits: new_bits,
+                            value: new_value,
+                        });
+                    }
+                }
+                BuiltinData::Splitter(range) | BuiltinData::Merger(range) => {
+                    let mut new = *range;
+                    let max = BitRange::MAX_BIT;
+                    ui.add(egui::Slider::new(&mut new.lo, 0..=max).text("lowest bit"));
+                    ui.add(egui::Slider::new(&mut new.hi, 0..=max).text("highest bit"));
+                    new.hi = new.hi.max(new.lo);
+                    if new != *range {
+                        reconfigure = Some(match builtin.ty {
+                            BuiltinDeviceTy::Merger => BuiltinData::Merger(new),
+                            _ => BuiltinData::Splitter(new),
+                        });
+                    }
+                }
+                BuiltinData::Rom(cfg) | BuiltinData::Ram(cfg) => {
+                    let mut new = *cfg;
+                    ui.add(egui::Slider::new(&mut new.addr_bits, 1..=16).text("address bits"));
+                    ui.add(egui::Slider::new(&mut new.data_bits, 1..=32).text("data bits"));
+                    if new != *cfg {
+                        reconfigure = Some(match builtin.ty {
+                            BuiltinDeviceTy::Ram => BuiltinData::Ram(new),
+                            _ => BuiltinData::Rom(new),
+                        });
+                    }
+                }
+            }
+            if let Some(NativeDevice::Ram(ram)) = sim.native_mut(builtin.region.min) {
+                ui.checkbox(&mut ram.persist, "Keep contents when the project is loaded");
+            }
+            match sim.native_mut(builtin.region.min) {
+                Some(NativeDevice::Clock(clock)) => {
+                    ui.add(egui::Slider::new(&mut clock.period, 2..=200).text("period (ticks)"));
+                    let max_duty = clock.period - 1;
+                    ui.add(egui::Slider::new(&mut clock.duty, 1..=max_duty).text("duty (ticks)"));
+                    ui.horizontal(|ui| {
+                        let label = if clock.paused { "Resume" } else { "Pause" };
+                        if ui.button(label).clicked() {
+                            clock.paused = !clock.paused;
+                        }
+                        if ui
+                            .add_enabled(clock.paused, egui::Button::new("Step"))
+                            .clicked()
+                        {
+                            clock.step();
+                        }
+                    });
+                }
+                Some(NativeDevice::PixelDisplay(display))
+                    if ui.button("Clear screen").clicked() =>
+                {
+                    display.pixels.fill(false);
+                }
+                Some(NativeDevice::Terminal(terminal)) if ui.button("Clear screen").clicked() => {
+                    terminal.text.clear();
+                }
+                Some(NativeDevice::PixelDisplay(_) | NativeDevice::Terminal(_)) => {}
+                Some(NativeDevice::Rom(Rom { config, data, .. }))
+                | Some(NativeDevice::Ram(Ram { config, data, .. })) => {
+                    ui.horizontal(|ui| {
+                        ui.label("File: ");
+                        ui.text_edit_singleline(&mut self.memory_path);
+                        if ui.button("Load").clicked() {
+                            match load_memory::<P>(&self.memory_path, *config) {
+                                Ok(new) => {
+                                    *data = new;
+                                    self.memory_error = None;
+                                }
+                                Err(err) => self.memory_error = Some(err),
+                            }
+                        }
+                    });
+                    if let Some(err) = &self.memory_error {
+                        ui.colored_label(ui.visuals().error_fg_color, err);
+                    }
+                    memory_editor(ui, *config, data);
+                }
+                Some(NativeDevice::Probe(probe)) => {
+                    ui.add(egui::Slider::new(&mut probe.depth, 10..=1000).text("depth (ticks)"));
+                    ui.horizontal(|ui| {
+                        if ui
+                            .checkbox(&mut probe.trigger, "Trigger on rising edge")
+                            .changed()
+                        {
+                            probe.samples.clear();
+                            probe.capturing = false;
+                        }
+                        if ui.button("Clear").clicked() {
+                            probe.samples.clear();
+                        }
+                    });
+                }
+                Some(NativeDevice::Keyboard(keyboard)) => {
+                    let code = keyboard.code;
+                    ui.label(format!("Last key: {:?} ({code:#04X})", code as char));
+                    ui.checkbox(&mut keyboard.ready, "Ready");
+                }
+                None => {}
+            }
+        }
+        if let Some((data, id)) = reconfigure.zip(self.selected_device) {
+            self.project.scenes[self.open_scene].reconfigure_builtin(id, data);
+        }
+        if ui.button("Close").clicked() {
+            self.selected_device = None;
+        }
+    }
+
+    fn show_recorder_panel<P: Platform>(&mut self, ui: &mut Ui) {
+        let scene = &mut self.project.scenes[self.open_scene];
+        let candidates = scene.signal_candidates();
+        let recorder = &mut scene.sim.recorder;
+        ui.heading("Recorder");
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut recorder.enabled, "Record");
+            if ui.button("Clear").clicked() {
+                recorder.clear();
+            }
+        });
+        ui.add(egui::Slider::new(&mut recorder.capacity, 100..=100_000).text("ticks kept"));
+        ui.label(format!("{} ticks recorded", recorder.len()));
+        ui.separator();
+
+        egui::ScrollArea::vertical()
+            .max_height(300.0)
+            .show(ui, |ui| {
+                egui::Grid::new("signals").show(ui, |ui| {
+                    for candidate in candidates {
+                        let mut recorded = recorder.is_recorded(candidate.addr);
+                        let label = format!("{}.{}", candidate.scope.join("."), candidate.name);
+                        if ui.checkbox(&mut recorded, label).changed() {
+                            match recorded {
+                                true => recorder.add_signal(candidate.clone()),
+                                false => recorder.remove_signal(candidate.addr),
+                            }
+                        }
+                        let signal = (recorder.signals.iter_mut())
+                            .find(|signal| signal.addr == candidate.addr);
+                        if let Some(signal) = signal {
+                            let width = signal.width;
+                            ui.add(
+                                egui::DragValue::new(&mut signal.width)
+                                    .range(1..=8)
+                                    .suffix(" bits"),
+                            );
+                            if signal.width != width {
+                                recorder.clear();
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.export_path);
+            if ui.button("Export VCD").clicked() {
+                let vcd = recorder.to_vcd();
+                self.export_status = Some(
+                    match P::write_file(self.export_path.trim(), vcd.as_bytes()) {
+                        Ok(()) => format!("Exported {} ticks", recorder.len()),
+                        Err(err) => format!("Failed to export : {err}"),
+                    },
+                );
+            }
+        });
+        if let Some(status) = &self.export_status {
+            ui.label(status);
+        }
+    }
+
+    fn show_timeline_panel(&mut self, ui: &mut Ui) {
+        let scene = &mut self.project.scenes[self.open_scene];
+        let tables = &self.project.library.tables;
+        ui.horizontal(|ui| {
+            let label = if scene.paused { "Resume" } else { "Pause" };
+            if ui.button(label).clicked() {
+                scene.paused = !scene.paused;
+                if !scene.paused {
+                    scene.hit_breakpoint = None;
+                    scene.timeline.truncate(scene.sim.tick);
+                }
+            }
+            if ui
+                .add_enabled(scene.paused, egui::Button::new("Step"))
+                .clicked()
+            {
+                scene.timeline.truncate(scene.sim.tick);
+                scene.step(tables);
+            }
+            ui.label(format!("tick {}", scene.sim.tick));
+            ui.separator();
+            if ui.button("Snapshot").clicked() {
+                scene.timeline.push(Snapshot::new(&scene.sim));
+            }
+            ui.add(
+                egui::DragValue::new(&mut scene.timeline.interval)
+                    .range(0..=10_000)
+                    .prefix("every ")
+                    .suffix(" ticks"),
+            );
+        });
+
+        // Scrubbing pauses the simulation at the selected snapshot
+        let count = scene.timeline.snapshots.len();
+        if count > 0 {
+            let current = (scene.timeline.snapshots.iter())
+                .rposition(|snapshot| snapshot.tick <= scene.sim.tick)
+                .unwrap_or(0);
+            let mut idx = current;
+            let timeline = &scene.timeline;
+            let slider = egui::Slider::new(&mut idx, 0..=count - 1)
+                .show_value(false)
+                .custom_formatter(|idx, _| timeline.snapshots[idx as usize].tick.to_string());
+            ui.spacing_mut().slider_width = 400.0;
+            if ui.add(slider).changed() && idx != current {
+                scene.paused = true;
+                let snapshot = scene.timeline.snapshots[idx].clone();
+                snapshot.restore(&mut scene.sim);
+            }
+        }
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.snapshot_name);
+            if ui.button("Save snapshot").clicked() {
+                let name = match self.snapshot_name.trim() {
+                    "" => format!("tick {}", scene.sim.tick),
+                    name => String::from(name),
+                };
+                scene.snapshots.push(NamedSnapshot {
+                    name,
+                    snapshot: Snapshot::new(&scene.sim),
+                });
+                self.snapshot_name.clear();
+            }
+        });
+        let mut rm_snapshot = None;
+        for (idx, named) in scene.snapshots.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(&named.name);
+                if ui.button("Restore").clicked() {
+                    scene.paused = true;
+                    named.snapshot.restore(&mut scene.sim);
+                }
+                if ui.button("Delete").clicked() {
+                    rm_snapshot = Some(idx);
+                }
+            });
+        }
+        if let Some(idx) = rm_snapshot {
+            scene.snapshots.remove(idx);
+        }
+    }
+
+    fn show_debug_panel(&mut self, ui: &mut Ui) {
+        let scene = &mut self.project.scenes[self.open_scene];
+        ui.heading("Breakpoints");
+        ui.separator();
+        if let Some(idx) = scene.hit_breakpoint {
+            ui.horizontal(|ui| {
+                let bp = &scene.breakpoints[idx];
+                let label = format!(
+                    "Paused at tick {} : {} {}",
+                    scene.sim.tick,
+                    scene.node_label(bp.node),
+                    bp.condition.label()
+                );
+                ui.colored_label(ui.visuals().warn_fg_color, label);
+                if ui.button("Resume").clicked() {
+                    scene.paused = false;
+                    scene.hit_breakpoint = None;
+                    scene.timeline.truncate(scene.sim.tick);
+                }
+            });
+        }
+
+        // The names and current states of the watched nodes, None if they were removed
+        let watched: Vec<(String, Option<u8>)> = (scene.breakpoints.iter())
+            .map(|bp| {
+                let addr = scene.node_info(bp.node).map(|info| info.addr);
+                let state = addr.map(|addr| scene.sim.get_node(addr).state());
+                (scene.node_label(bp.node), state)
+            })
+            .collect();
+        let mut rm_breakpoint = None;
+        egui::Grid::new("breakpoints").show(ui, |ui| {
+            let breakpoints = scene.breakpoints.iter_mut().zip(watched);
+            for (idx, (bp, (label, state))) in breakpoints.enumerate() {
+                ui.checkbox(&mut bp.enabled, "");
+                ui.label(label);
+
+                let equals = match bp.condition {
+                    Condition::Equals(value) => Condition::Equals(value),
+                    _ => Condition::Equals(state.unwrap_or(0)),
+                };
+                egui::ComboBox::from_id_source(("condition", idx))
+                    .selected_text(bp.condition.label())
+                    .show_ui(ui, |ui| {
+                        for condition in [Condition::Rises, Condition::Falls, equals] {
+                            let label = condition.label();
+                            ui.selectable_value(&mut bp.condition, condition, label);
+                        }
+                    });
+                if let Condition::Equals(value) = &mut bp.condition {
+                    ui.add(egui::DragValue::new(value).hexadecimal(2, false, true));
+                } else {
+                    ui.label("");
+                }
+                match state {
+                    Some(state) => ui.label(format!("now {state:#04X}")),
+                    None => ui.colored_label(ui.visuals().error_fg_color, "missing"),
+                };
+                if ui.button("Delete").clicked() {
+                    rm_breakpoint = Some(idx);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(idx) = rm_breakpoint {
+            scene.breakpoints.remove(idx);
+            scene.hit_breakpoint = None;
+        }
+
+        let label = match self.picking_breakpoint {
+            true => "Click a pin or wire...",
+            false => "Add breakpoint",
+        };
+        if ui
+            .selectable_label(self.picking_breakpoint, label)
+            .clicked()
+        {
+            self.picking_breakpoint = !self.picking_breakpoint;
+        }
+    }
+
+    fn show_nets_panel(&mut self, ui: &mut Ui) {
+        let scene = &self.project.scenes[self.open_scene];
+        ui.heading("Nets");
+        ui.separator();
+        let nets = scene.nets();
+        if nets.is_empty() {
+            ui.label("Place a tunnel to create a net");
+        }
+        egui::Grid::new("nets").show(ui, |ui| {
+            for net in nets {
+                let rs = ui.selectable_label(
+                    net.tunnels
+                        .iter()
+                        .any(|id| Some(*id) == self.selected_device),
+                    &net.name,
+                );
+                if rs.clicked() {
+                    self.selected_device = net.drivers.first().or(net.tunnels.first()).copied();
+                }
+                ui.label(format!("{} tunnels", net.tunnels.len()));
+                match net.error {
+                    Some(err) => ui.colored_label(ui.visuals().error_fg_color, err.message()),
+                    None => {
+                        let addr = scene.devices[&net.drivers[0]].r_nodes()[0].0;
+                        ui.label(format!("state: {}", scene.sim.get_node(addr).state()))
+                    }
+                };
+                ui.end_row();
+            }
+        });
+    }
+
     fn show_tpanel<P: Platform>(
         &mut self,
         ui: &mut Ui,
@@ -652,6 +1156,53 @@ impl WorkspacePage {
         if ui.button("pack").clicked() {
             _ = self.toggle_menu(WorkspaceMenu::CreateChip);
         }
+        if ui.button("nets").clicked() {
+            self.show_nets = !self.show_nets;
+        }
+        if ui.button("record").clicked() {
+            self.show_recorder = !self.show_recorder;
+        }
+        if ui.button("timeline").clicked() {
+            self.show_timeline = !self.show_timeline;
+        }
+        if ui.button("debug").clicked() {
+            self.show_debug = !self.show_debug;
+            self.picking_breakpoint = false;
+        }
+        if ui.button("waves").clicked() {
+            self.waveforms = match self.waveforms {
+                Some(_) => None,
+                None => Some(WaveformViewer::new(
+                    &self.project.scenes[self.open_scene].sim.recorder,
+                )),
+            };
+        }
+        if ui.button("new table").clicked() {
+            self.expr_editor = None;
+            self.table_editor = match self.table_editor {
+                Some(_) => None,
+                None => Some(TableEditor::default()),
+            };
+        }
+        if ui.button("new expr").clicked() {
+            self.table_editor = None;
+            self.expr_editor = match self.expr_editor {
+                Some(_) => None,
+                None => Some(ExprEditor::default()),
+            };
+        }
+        if ui.button("tests").clicked() {
+            self.test_editor = match self.test_editor {
+                Some(_) => None,
+                None => Some(TestEditor::default()),
+            };
+        }
+        if ui.button("table").clicked() {
+            self.truth_table = match self.truth_table {
+                Some(_) => None,
+                None => Some(TruthTableViewer::default()),
+            };
+        }
         ui.label("-");
 
         let mut rm_scene = None;
@@ -676,6 +1227,50 @@ impl WorkspacePage {
         }
     }
 }
+/// Reads the contents of a memory from a raw binary file, or an Intel HEX file if it ends with `.hex`.
+fn load_memory<P: Platform>(path: &str, cfg: MemoryConfig) -> Result<Vec<u32>, String> {
+    let bytes = P::read_file(path.trim()).map_err(|err| err.to_string())?;
+    let is_hex = [".hex", ".ihx"]
+        .iter()
+        .any(|ext| path.trim().to_lowercase().ends_with(ext));
+    let bytes = match is_hex {
+        true => {
+            let text = String::from_utf8(bytes).map_err(|_| "file is not text".to_string())?;
+            ihex::parse(&text)?
+        }
+        false => bytes,
+    };
+    Ok(cfg.words_from_bytes(&bytes))
+}
+
+/// Shows the words of a memory as a grid of hex values that can be edited.
+fn memory_editor(ui: &mut Ui, cfg: MemoryConfig, data: &mut [u32]) {
+    const COLUMNS: usize = 8;
+    let digits = (cfg.data_bits as usize).div_ceil(4);
+    let addr_digits = (cfg.addr_bits as usize).div_ceil(4).max(1);
+    let rows = data.len().div_ceil(COLUMNS);
+    let row_height = ui.spacing().interact_size.y;
+    egui::ScrollArea::vertical()
+        .max_height(200.0)
+        .show_rows(ui, row_height, rows, |ui, range| {
+            egui::Grid::new("memory").show(ui, |ui| {
+                for row in range {
+                    let start = row * COLUMNS;
+                    ui.monospace(format!("{start:0addr_digits$X}:"));
+                    let end = (start + COLUMNS).min(data.len());
+                    for word in &mut data[start..end] {
+                        ui.add(
+                            egui::DragValue::new(word)
+                                .hexadecimal(digits, false, true)
+                                .range(0..=cfg.mask()),
+                        );
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+}
+
 impl<P: Platform> Page<P> for WorkspacePage {
     fn hide_top_panel(&self) -> bool {
         true
@@ -698,6 +1293,7 @@ impl<P: Platform> Page<P> for WorkspacePage {
                 scene,
                 self.snap_to_grid,
                 self.show_grid,
+                self.selected_device,
                 out,
             );
 
@@ -733,31 +1329,89 @@ impl<P: Platform> Page<P> for WorkspacePage {
             None
         };
 
+        // A pin or wire was picked for a new breakpoint, instead of being connected
+        if self.picking_breakpoint {
+            let scene = &mut self.project.scenes[self.open_scene];
+            let wire = (out.clicked_wire.take())
+                .and_then(|((input, output), ..)| scene.wires.get(scene.find_wire(input, output)?));
+            let node = (out.clicked_node.take())
+                .or(out.rclicked_node.take())
+                .map(|(ident, ..)| ident)
+                .or(wire.map(|wire| wire.input));
+            if let Some(node) = node {
+                scene.breakpoints.push(Breakpoint::new(node));
+                self.picking_breakpoint = false;
+            }
+        }
+
         // Update placing wire
         if let Some((ident, addr, _ty)) = out.rclicked_node {
-            if let Some(WirePlacement { src, anchors }) = self.wire_placement.clone() {
+            if let Some(WirePlacement {
+                src,
+                anchors,
+                junction,
+            }) = self.wire_placement.clone()
+            {
                 if src.0 != ident {
                     self.wire_placement = None;
 
                     let scene = &mut self.project.scenes[self.open_scene];
 
-                    _ = scene.rm_wire_by_target(addr);
+                    _ = scene.rm_wire_by_target(addr, src.0);
 
-                    let new_src = Source::new_addr(src.1);
-                    scene.sim.nodes[addr.0 as usize].set_source(new_src);
+                    let route = self.auto_route && anchors.is_empty();
                     scene.wires.push(Wire {
-                        input: src.0,
-                        output: ident,
-                        anchors,
+                        junction,
+                        ..Wire::new(src.0, ident, anchors)
                     });
+                    scene.connect_node(ident);
+                    if route {
+                        _ = scene.route_wire(scene.wires.len() - 1);
+                    }
                 }
             } else {
                 self.wire_placement = Some(WirePlacement {
                     src: (ident, addr),
                     anchors: vec![],
+                    junction: None,
                 });
             }
         }
+
+        // Branch a new wire off of a clicked wire
+        let mut started_junction = false;
+        if let Some(((input, output), segment, pos)) = out.clicked_wire {
+            let scene = &self.project.scenes[self.open_scene];
+            match &self.wire_placement {
+                None => {
+                    // Drawing the scene can reorder the wires, so look the wire up again
+                    let branch = scene.find_wire(input, output).and_then(|idx| {
+                        let wire = &scene.wires[idx];
+                        let info = scene.node_info(wire.input)?;
+                        Some((wire, info, scene.junction_at(idx, segment, pos)?))
+                    });
+                    if let Some((wire, info, junction)) = branch {
+                        self.wire_placement = Some(WirePlacement {
+                            src: (wire.input, info.addr),
+                            anchors: vec![],
+                            junction: Some(junction),
+                        });
+                        started_junction = true;
+                    }
+                }
+                // The wire was clicked twice (double clicking inserts an anchor instead).
+                Some(placement) if placement.junction.is_some() && placement.anchors.is_empty() => {
+                    self.wire_placement = None;
+                }
+                Some(_) => {}
+            }
+        }
+        if let Some(id) = out.clicked_device {
+            if self.selected_device != Some(id) {
+                self.binding_key = false;
+            }
+            self.selected_device = Some(id);
+        }
         if let Some((_ident, addr, ty)) = out.clicked_node {
             if matches!(ty, IoType::Input) {
                 let scene = &mut self.project.scenes[self.open_scene];
@@ -768,7 +1422,7 @@ impl<P: Platform> Page<P> for WorkspacePage {
 
         // ---- Place Wire Anchors
         if let Some(bg_rs) = scene_rs {
-            if bg_rs.clicked() {
+            if bg_rs.clicked() && !started_junction {
                 let scene = &mut self.project.scenes[self.open_scene];
                 let ptr_pos = bg_rs.interact_pointer_pos().unwrap();
                 let ptr_pos = vec2(ptr_pos.x, ptr_pos.y);
@@ -779,7 +1433,12 @@ impl<P: Platform> Page<P> for WorkspacePage {
         }
 
         // ---- Draw Wire Being Placed ----
-        if let Some(WirePlacement { src, anchors }) = &self.wire_placement {
+        if let Some(WirePlacement {
+            src,
+            anchors,
+            junction,
+        }) = &self.wire_placement
+        {
             let scene = &mut self.project.scenes[self.open_scene];
             if let Some(info) = scene.node_info(src.0) {
                 let state = scene.sim.get_node(info.addr).state();
@@ -791,7 +1450,9 @@ impl<P: Platform> Page<P> for WorkspacePage {
                     scene.transform,
                     state,
                     true,
-                    info.pos,
+                    junction
+                        .and_then(|junction| scene.junction_pos(src.0, junction))
+                        .unwrap_or(info.pos),
                     dst,
                     anchors,
                 );
@@ -818,6 +1479,107 @@ impl<P: Platform> Page<P> for WorkspacePage {
             });
         }
 
+        // Show the nets of the open scene
+        if self.show_nets {
+            egui::Window::new("nets")
+                .anchor(egui::Align2::LEFT_TOP, [10.0, 40.0])
+                .resizable(false)
+                .collapsible(false)
+                .title_bar(false)
+                .show(ui.ctx(), |ui| self.show_nets_panel(ui));
+        }
+
+        // Show the recorded signals of the open scene
+        if self.show_recorder {
+            egui::Window::new("recorder")
+                .anchor(egui::Align2::RIGHT_TOP, [-110.0, 40.0])
+                .resizable(false)
+                .collapsible(false)
+                .title_bar(false)
+                .show(ui.ctx(), |ui| self.show_recorder_panel::<P>(ui));
+        }
+
+        // Show the snapshots of the open scene
+        if self.show_timeline {
+            egui::Window::new("timeline")
+                .anchor(egui::Align2::CENTER_BOTTOM, [0.0, -10.0])
+                .resizable(false)
+                .collapsible(false)
+                .title_bar(false)
+                .show(ui.ctx(), |ui| self.show_timeline_panel(ui));
+        }
+
+        // Show the breakpoints of the open scene
+        if self.show_debug {
+            egui::Window::new("debug")
+                .anchor(egui::Align2::RIGHT_BOTTOM, [-110.0, -10.0])
+                .resizable(false)
+                .collapsible(false)
+                .title_bar(false)
+                .show(ui.ctx(), |ui| self.show_debug_panel(ui));
+        }
+
+        // Show the truth table a new chip is made from
+        if let Some(editor) = &mut self.table_editor {
+            egui::Window::new("table editor")
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .collapsible(false)
+                .title_bar(false)
+                .show(ui.ctx(), |ui| editor.show(ui, &mut self.project.library));
+        }
+
+        // Show the expressions a new chip is compiled from
+        if let Some(editor) = &mut self.expr_editor {
+            egui::Window::new("expression editor")
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .collapsible(false)
+                .title_bar(false)
+                .show(ui.ctx(), |ui| editor.show(ui, &mut self.project.library));
+        }
+
+        // Show the tests of the library chips
+        if let Some(editor) = &mut self.test_editor {
+            egui::Window::new("test editor")
+                .anchor(egui::Align2::LEFT_TOP, [10.0, 40.0])
+                .collapsible(false)
+                .title_bar(false)
+                .show(ui.ctx(), |ui| editor.show(ui, &mut self.project.library));
+        }
+
+        // Show the recorded signals of the open scene as they're recorded
+        if let Some(viewer) = &mut self.waveforms {
+            egui::Window::new("waveforms")
+                .anchor(egui::Align2::CENTER_TOP, [0.0, 40.0])
+                .collapsible(false)
+                .title_bar(false)
+                .show(ui.ctx(), |ui| {
+                    viewer.show(ui, &mut self.project.scenes[self.open_scene])
+                });
+        }
+
+        // Show the truth table of the open scene or a library chip, as they're edited
+        if let Some(viewer) = &mut self.truth_table {
+            egui::Window::new("truth table")
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .collapsible(false)
+                .title_bar(false)
+                .max_height(500.0)
+                .show(ui.ctx(), |ui| {
+                    let scene = &self.project.scenes[self.open_scene];
+                    viewer.show(ui, scene, &self.project.library)
+                });
+        }
+
+        // Show properties of the selected device
+        if self.selected_device.is_some() {
+            egui::Window::new("device")
+                .anchor(egui::Align2::LEFT_BOTTOM, [10.0, -10.0])
+                .resizable(false)
+                .collapsible(false)
+                .title_bar(false)
+                .show(ui.ctx(), |ui| self.show_device_panel::<P>(ui));
+        }
+
         // Show menu if one is open
         if let Some(menu) = self.open_menu {
             egui::Window::new("menu")
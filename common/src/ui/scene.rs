@@ -1,7 +1,12 @@
+use crate::geom::{line_contains_point, project_point_onto_line, Transform};
 use crate::save::{IoType, Library};
-use crate::sim::scene::{ExternalNodes, NodeIdent, Scene, Side, UNIT};
-use crate::sim::{Sim, Source};
-use crate::ui::{pages::PageOutput, Transform};
+use crate::sim::native::NativeDevice;
+use crate::sim::scene::{
+    BuiltinData, BuiltinDevice, BuiltinDeviceTy, Device, Edge, ExternalNodes, Key, NodeIdent,
+    Scene, SceneId, Side, WireEnd, PIXEL_SIZE, UNIT,
+};
+use crate::sim::{NodeAddr, Sim};
+use crate::ui::pages::PageOutput;
 
 use egui::epaint::QuadraticBezierShape;
 use egui::{Align2, Button, Color32, Id, Rect, Response, Sense, Stroke, Ui};
@@ -10,9 +15,20 @@ use glam::{vec2, Vec2};
 #[derive(Clone, Copy)]
 enum LabelPlacement {
     Top,
+    Bottom,
     Left,
     Right,
 }
+impl LabelPlacement {
+    fn outside(edge: Edge) -> Self {
+        match edge {
+            Edge::Left => Self::Left,
+            Edge::Top => Self::Top,
+            Edge::Right => Self::Right,
+            Edge::Bottom => Self::Bottom,
+        }
+    }
+}
 
 fn place_label(
     ui: &mut Ui,
@@ -29,6 +45,10 @@ fn place_label(
             bounds.center_top() - egui::vec2(0.0, fsize * 0.5),
             Align2::CENTER_CENTER,
         ),
+        LabelPlacement::Bottom => (
+            bounds.center_bottom() + egui::vec2(0.0, fsize * 0.5),
+            Align2::CENTER_CENTER,
+        ),
         LabelPlacement::Left => (bounds.left_center(), Align2::RIGHT_CENTER),
         LabelPlacement::Right => (bounds.right_center(), Align2::LEFT_CENTER),
     };
@@ -52,9 +72,14 @@ pub fn show_scene<P>(
     scene: &mut Scene,
     snap_to_grid: bool,
     show_grid: bool,
+    selected: Option<SceneId>,
     out: &mut PageOutput<P>,
 ) -> Response {
-    scene.sim.update(&library.tables);
+    scene.connect_nets();
+    scene.connect_pulls();
+    if !scene.paused {
+        scene.step(&library.tables);
+    }
 
     let screen_size = ui.clip_rect().size();
     let screen_size = glam::vec2(screen_size.x, screen_size.y);
@@ -125,6 +150,9 @@ pub fn show_scene<P>(
 
     // Draw Wires
     let mut rm_wire = None;
+    let mut edits = vec![];
+    let ptr = ui.ctx().pointer_latest_pos().unwrap_or(egui::Pos2::ZERO);
+    let ptr = t.inv() * vec2(ptr.x, ptr.y);
     for (idx, wire) in scene.wires.iter().enumerate() {
         let Some(src) = scene.node_info(wire.input) else {
             rm_wire = Some(idx);
@@ -134,34 +162,108 @@ pub fn show_scene<P>(
             rm_wire = Some(idx);
             continue;
         };
+        // A branch whose parent wire is gone is removed along with it
+        let Some(start) = scene.wire_start(idx) else {
+            rm_wire = Some(idx);
+            continue;
+        };
         let state = scene.sim.get_node(src.addr).state();
-        let (_clicked, rclicked) = draw_wire(
+
+        // Interact with the handles before drawing the wire, so they take priority.
+        let ends = (wire.input, wire.output);
+        let handles = wire_handles(ui, t, ends, start, dst.pos, &wire.anchors, &mut edits);
+
+        let rs = draw_wire(
             ui,
             scene.transform,
             state,
             false,
-            src.pos,
+            start,
             dst.pos,
             &wire.anchors,
         );
-        if rclicked {
-            rm_wire = Some(idx);
+        if rs.rclicked && !handles.consumed_click {
+            edits.push(WireEdit::Remove(ends));
+        }
+        if let Some(segment) = rs.hovered_segment {
+            if rs.double_clicked {
+                edits.push(WireEdit::InsertAnchor(ends, segment, ptr));
+            } else if rs.clicked {
+                // The point on the wire a new wire can branch off from
+                let points: Vec<Vec2> = std::iter::once(start)
+                    .chain(wire.anchors.iter().copied())
+                    .chain(std::iter::once(dst.pos))
+                    .collect();
+                let line = (points[segment], points[segment + 1]);
+                let pos = project_point_onto_line(ptr, line);
+                out.clicked_wire = Some((ends, segment, pos));
+            }
+        }
+        if wire.junction.is_some() {
+            let colors = [Color32::from_rgb(64, 2, 0), Color32::from_rgb(235, 19, 12)];
+            let color = colors[(state != 0) as usize];
+            ui.painter()
+                .circle_filled(t * egui::pos2(start.x, start.y), t * UNIT * 0.25, color);
+        }
+        if rs.hovered_segment.is_some() || handles.active {
+            handles.paint(ui, t, state);
         }
     }
     if let Some(idx) = rm_wire {
-        let wire = scene.wires.remove(idx);
-        if let Some(dst_info) = scene.node_info(wire.output) {
-            scene.sim.nodes[dst_info.addr.0 as usize].set_source(Source::new_none());
+        scene.remove_wire(idx);
+    }
+    for edit in edits {
+        // Edits find their wire by it's ends, as earlier edits can reorder the wires
+        let (input, output) = edit.wire();
+        let Some(idx) = scene.find_wire(input, output) else {
+            continue;
+        };
+        match edit {
+            WireEdit::MoveAnchor(_, anchor, delta) => {
+                let wire = &mut scene.wires[idx];
+                wire.anchors[anchor] += delta;
+                wire.routed = false;
+            }
+            WireEdit::SnapAnchor(_, anchor) => {
+                if snap_to_grid {
+                    let anchor = &mut scene.wires[idx].anchors[anchor];
+                    *anchor = UNIT * (*anchor / UNIT).round();
+                }
+            }
+            WireEdit::RemoveAnchor(_, anchor) => {
+                let wire = &mut scene.wires[idx];
+                wire.anchors.remove(anchor);
+                wire.routed = false;
+            }
+            WireEdit::InsertAnchor(_, segment, mut pos) => {
+                if snap_to_grid {
+                    pos = UNIT * (pos / UNIT).round();
+                }
+                let wire = &mut scene.wires[idx];
+                wire.anchors.insert(segment, pos);
+                wire.routed = false;
+            }
+            WireEdit::MoveEnd(_, end, pos) => {
+                if let Some(ident) = scene.node_at(pos) {
+                    _ = scene.reconnect_wire(idx, end, ident);
+                }
+            }
+            WireEdit::Remove(_) => _ = scene.remove_wire(idx),
         }
     }
 
     // Draw Devices
     let mut rm_device = None;
+    let mut moved_devices = vec![];
     for (device_id, device) in &mut scene.devices {
-        let bounds = device.bounds();
+        let bounds = Rect::from(device.bounds());
         let color = Color32::from_gray(200);
 
         ui.painter().rect_filled(t * bounds, t * 4.0, color);
+        if selected == Some(*device_id) {
+            let stroke = Stroke::new(2.0, ui.visuals().selection.bg_fill);
+            ui.painter().rect_stroke(t * bounds, t * 4.0, stroke);
+        }
 
         let rs = ui.interact(
             t * bounds,
@@ -172,6 +274,16 @@ pub fn show_scene<P>(
             // remove device from scene
             rm_device = Some(*device_id);
         }
+        if rs.clicked() {
+            out.clicked_device = Some(*device_id);
+        }
+        if let Device::Builtin(builtin) = device {
+            interact_builtin(ui, builtin, &rs, &mut scene.sim);
+            draw_builtin(ui, t, builtin, &scene.sim);
+            if let Some(NativeDevice::Terminal(term)) = scene.sim.native(builtin.region.min) {
+                show_terminal(ui, t, *device_id, bounds, &term.text);
+            }
+        }
 
         device.pos_mut().x += t.inv() * rs.drag_delta().x;
         device.pos_mut().y += t.inv() * rs.drag_delta().y;
@@ -180,62 +292,393 @@ pub fn show_scene<P>(
             let off = device.size() * 0.5;
             *device.pos_mut() = off + UNIT * ((device.pos() - off) / UNIT).round();
         }
+        // Routing is too slow to do every frame, so wires are rerouted once the device is dropped
+        if rs.drag_stopped() {
+            moved_devices.push(*device_id);
+        }
 
         place_label(ui, t, bounds, device.name(), LabelPlacement::Top);
 
         let colors = [Color32::BLACK, Color32::RED];
 
-        for (i, (addr, name, ty)) in device.l_nodes().iter().enumerate() {
-            let node = scene.sim.get_node(*addr);
-            let color = colors[node.state() as usize];
-
-            let center = egui::pos2(bounds.min.x, bounds.min.y + i as f32 * UNIT + UNIT * 0.5);
-            let bounds = Rect::from_center_size(center, egui::vec2(UNIT, UNIT));
+        for side in [Side::Left, Side::Right] {
+            let nodes = match side {
+                Side::Left => device.l_nodes(),
+                Side::Right => device.r_nodes(),
+            };
+            for (i, (addr, name, ty)) in nodes.iter().enumerate() {
+                let Some(info) = device.node_info(side, i as u32) else {
+                    continue;
+                };
+                let node = scene.sim.get_node(*addr);
+                let color = colors[(node.state() != 0) as usize];
+
+                let center = egui::pos2(info.pos.x, info.pos.y);
+                let bounds = Rect::from_center_size(center, egui::vec2(UNIT, UNIT));
+
+                let ident = match side {
+                    Side::Left => NodeIdent::DeviceL(*device_id, i as u32),
+                    Side::Right => NodeIdent::DeviceR(*device_id, i as u32),
+                };
+                let rs = ui.interact(
+                    t * bounds,
+                    Id::from(format!("{device_id:?}{side:?}{i}")),
+                    Sense::click(),
+                );
+                if rs.clicked() {
+                    out.clicked_node = Some((ident, *addr, *ty));
+                }
+                if rs.secondary_clicked() {
+                    out.rclicked_node = Some((ident, *addr, *ty));
+                }
 
-            let rs = ui.interact(
-                t * bounds,
-                Id::from(format!("{device_id:?}l{i}")),
-                Sense::click(),
-            );
-            if rs.clicked() {
-                out.clicked_node = Some((NodeIdent::DeviceL(*device_id, i as u32), *addr, *ty));
-            }
-            if rs.secondary_clicked() {
-                out.rclicked_node = Some((NodeIdent::DeviceL(*device_id, i as u32), *addr, *ty));
+                ui.painter()
+                    .circle_filled(t * center, t * UNIT * 0.4, color);
+                place_label(ui, t, bounds, name, LabelPlacement::outside(info.edge));
             }
+        }
+    }
+    for id in moved_devices {
+        scene.reroute_device(id);
+    }
+    if let Some(id) = rm_device {
+        _ = scene.remove_device(id);
+    }
 
-            ui.painter()
-                .circle_filled(t * center, t * UNIT * 0.4, color);
-            place_label(ui, t, bounds, name, LabelPlacement::Left);
+    // Mark the nodes with breakpoints, and highlight the one that paused the simulation
+    for (idx, bp) in scene.breakpoints.iter().enumerate() {
+        let Some(info) = scene.node_info(bp.node) else {
+            continue;
+        };
+        let pos = t * egui::pos2(info.pos.x, info.pos.y);
+        if scene.hit_breakpoint == Some(idx) {
+            let stroke = Stroke::new(3.0, Color32::YELLOW);
+            ui.painter().circle_stroke(pos, t * UNIT * 0.6, stroke);
+        } else if bp.enabled {
+            let stroke = Stroke::new(1.5, Color32::from_rgb(200, 120, 0));
+            ui.painter().circle_stroke(pos, t * UNIT * 0.45, stroke);
         }
-        for (i, (addr, name, ty)) in device.r_nodes().iter().enumerate() {
-            let node = scene.sim.get_node(*addr);
-            let color = colors[node.state() as usize];
+    }
+    rs
+}
 
-            let center = egui::pos2(bounds.max.x, bounds.min.y + i as f32 * UNIT + UNIT * 0.5);
-            let bounds = Rect::from_center_size(center, egui::vec2(UNIT, UNIT));
+/// Handles the interactions with the body of a builtin device.
+fn interact_builtin(ui: &Ui, device: &BuiltinDevice, rs: &Response, sim: &mut Sim) {
+    match (device.ty, &device.data) {
+        (BuiltinDeviceTy::Button, BuiltinData::Button { key }) => {
+            let key_down = key.and_then(Key::to_egui).is_some_and(|key| {
+                !ui.ctx().wants_keyboard_input() && ui.input(|input| input.key_down(key))
+            });
+            let pressed = rs.is_pointer_button_down_on() || key_down;
+            sim.mut_node(device.r_nodes[0].0).set_state(pressed as u8);
+        }
+        (BuiltinDeviceTy::Switch, _) if rs.clicked() => {
+            let addr = device.r_nodes[0].0;
+            sim.set_node(addr, sim.get_node(addr).toggle_state());
+        }
+        (BuiltinDeviceTy::Keyboard, _) if !ui.ctx().wants_keyboard_input() => {
+            let Some(NativeDevice::Keyboard(keyboard)) = sim.native_mut(device.region.min) else {
+                return;
+            };
+            ui.input(|input| {
+                for event in &input.events {
+                    match event {
+                        egui::Event::Text(text) => text.chars().for_each(|c| keyboard.push(c)),
+                        egui::Event::Key {
+                            key, pressed: true, ..
+                        } => match key {
+                            egui::Key::Enter => keyboard.push('\n'),
+                            egui::Key::Backspace => keyboard.push('\x08'),
+                            egui::Key::Escape => keyboard.push('\x1b'),
+                            _ => {}
+                        },
+                        _ => {}
+                    }
+                }
+            });
+        }
+        _ => {}
+    }
+}
 
-            let rs = ui.interact(
-                t * bounds,
-                Id::from(format!("{device_id:?}r{i}")),
-                Sense::click(),
+/// Shows the text of a terminal device in a scrollable box covering it's body.
+fn show_terminal(ui: &mut Ui, t: Transform, id: SceneId, bounds: Rect, text: &str) {
+    let rect = t * bounds.shrink(UNIT * 0.25);
+    ui.painter().rect_filled(rect, 0.0, Color32::BLACK);
+    ui.allocate_ui_at_rect(rect, |ui| {
+        egui::ScrollArea::vertical()
+            .id_source(Id::from("terminal").with(id))
+            .drag_to_scroll(false)
+            .stick_to_bottom(true)
+            .auto_shrink(false)
+            .show(ui, |ui| {
+                let text = egui::RichText::new(text)
+                    .monospace()
+                    .size(t * UNIT * 0.5)
+                    .color(Color32::LIGHT_GREEN);
+                ui.add(egui::Label::new(text).wrap());
+            });
+    });
+}
+
+/// Draws the contents of the body of a builtin device.
+fn draw_builtin(ui: &Ui, t: Transform, device: &BuiltinDevice, sim: &Sim) {
+    let bounds = Rect::from(device.bounds());
+    let on_color = Color32::RED;
+    let off_color = Color32::from_gray(60);
+    let color = |addr: NodeAddr| match sim.get_node(addr).state() {
+        0 => off_color,
+        _ => on_color,
+    };
+    let p = ui.painter();
+
+    match device.ty {
+        BuiltinDeviceTy::Button => {
+            let color = color(device.r_nodes[0].0);
+            p.circle_filled(t * bounds.center(), t * UNIT * 0.35, color);
+        }
+        BuiltinDeviceTy::Switch => {
+            let color = color(device.r_nodes[0].0);
+            let rect = bounds.shrink(UNIT * 0.2);
+            p.rect_filled(t * rect, 0.0, off_color);
+            let knob = match sim.get_node(device.r_nodes[0].0).state() {
+                0 => Rect::from_min_max(rect.min, egui::pos2(rect.max.x, rect.center().y)),
+                _ => Rect::from_min_max(egui::pos2(rect.min.x, rect.center().y), rect.max),
+            };
+            p.rect_filled(t * knob, 0.0, color);
+        }
+        BuiltinDeviceTy::Light => {
+            let color = color(device.l_nodes[0].0);
+            p.circle_filled(t * bounds.center(), t * UNIT * 0.4, color);
+        }
+        BuiltinDeviceTy::SevenSegment => {
+            let mut segments = 0;
+            for (idx, (addr, ..)) in device.l_nodes.iter().enumerate() {
+                segments |= ((sim.get_node(*addr).state() != 0) as u8) << idx;
+            }
+            draw_segments(
+                ui,
+                t,
+                bounds.shrink(UNIT * 0.5),
+                segments,
+                on_color,
+                off_color,
+            );
+        }
+        BuiltinDeviceTy::HexDisplay => {
+            let mut digit = 0;
+            for (idx, (addr, ..)) in device.l_nodes.iter().enumerate() {
+                digit |= ((sim.get_node(*addr).state() != 0) as usize) << idx;
+            }
+            let segments = HEX_SEGMENTS[digit & 0xF];
+            draw_segments(
+                ui,
+                t,
+                bounds.shrink(UNIT * 0.5),
+                segments,
+                on_color,
+                off_color,
             );
-            if rs.clicked() {
-                out.clicked_node = Some((NodeIdent::DeviceR(*device_id, i as u32), *addr, *ty));
+        }
+        BuiltinDeviceTy::Ground | BuiltinDeviceTy::Power | BuiltinDeviceTy::Constant => {
+            let value = device.ty.constant_value(&device.data).unwrap_or(0);
+            let digits = device.r_nodes.len().div_ceil(4);
+            p.text(
+                t * bounds.center(),
+                Align2::CENTER_CENTER,
+                format!("{value:0digits$X}"),
+                egui::FontId::monospace(t * UNIT * 0.6),
+                ui.visuals().text_color(),
+            );
+        }
+        BuiltinDeviceTy::Probe => {
+            let Some(NativeDevice::Probe(probe)) = sim.native(device.region.min) else {
+                return;
+            };
+            let rect = bounds.shrink(UNIT * 0.25);
+            p.rect_filled(t * rect, 0.0, Color32::BLACK);
+            let depth = probe.depth.max(1) as usize;
+            let step = rect.width() / depth as f32;
+            // A scrolling trace ends at the right edge, a triggered one starts at the left.
+            let start = match probe.trigger {
+                true => 0,
+                false => depth.saturating_sub(probe.samples.len()),
+            };
+            let (low, high) = (rect.max.y - UNIT * 0.25, rect.min.y + UNIT * 0.25);
+            let mut points = vec![];
+            for (idx, state) in probe.samples.iter().enumerate() {
+                let y = if *state != 0 { high } else { low };
+                let x = rect.min.x + (start + idx) as f32 * step;
+                points.push(t * egui::pos2(x, y));
+                points.push(t * egui::pos2(x + step, y));
             }
-            if rs.secondary_clicked() {
-                out.rclicked_node = Some((NodeIdent::DeviceR(*device_id, i as u32), *addr, *ty));
+            let stroke = Stroke::new(t * 1.5, Color32::LIGHT_GREEN);
+            p.add(egui::Shape::line(points, stroke));
+        }
+        BuiltinDeviceTy::PixelDisplay => {
+            let Some(NativeDevice::PixelDisplay(display)) = sim.native(device.region.min) else {
+                return;
+            };
+            let cfg = display.config;
+            let size = egui::vec2(cfg.width as f32, cfg.height as f32) * PIXEL_SIZE;
+            let grid = Rect::from_center_size(bounds.center(), size);
+            p.rect_filled(t * grid, 0.0, Color32::BLACK);
+            for y in 0..cfg.height {
+                for x in 0..cfg.width {
+                    if !display.pixel(x, y) {
+                        continue;
+                    }
+                    let min = grid.min + egui::vec2(x as f32, y as f32) * PIXEL_SIZE;
+                    let pixel = Rect::from_min_size(min, egui::Vec2::splat(PIXEL_SIZE));
+                    p.rect_filled(t * pixel, 0.0, on_color);
+                }
             }
+        }
+        _ => {}
+    }
+}
+
+/// The segments (bit 0 = a, ..., bit 6 = g) that are lit to show each hex digit.
+const HEX_SEGMENTS: [u8; 16] = [
+    0x3F, 0x06, 0x5B, 0x4F, 0x66, 0x6D, 0x7D, 0x07, 0x7F, 0x6F, 0x77, 0x7C, 0x39, 0x5E, 0x79, 0x71,
+];
+
+/// Draws a seven segment digit in `rect`.
+/// `segments` has bit 0 = a, ..., bit 6 = g, bit 7 = the decimal point.
+fn draw_segments(ui: &Ui, t: Transform, rect: Rect, segments: u8, on: Color32, off: Color32) {
+    let th = rect.width() * 0.15;
+    let (l, r, top, bot) = (rect.min.x, rect.max.x - th, rect.min.y, rect.max.y - th);
+    let mid = rect.center().y - th * 0.5;
+    let h = |x: f32, y: f32| Rect::from_min_size(egui::pos2(x + th, y), egui::vec2(r - l - th, th));
+    let v = |x: f32, y0: f32, y1: f32| {
+        Rect::from_min_max(egui::pos2(x, y0 + th), egui::pos2(x + th, y1))
+    };
+    let rects = [
+        h(l, top),      // a
+        v(r, top, mid), // b
+        v(r, mid, bot), // c
+        h(l, bot),      // d
+        v(l, mid, bot), // e
+        v(l, top, mid), // f
+        h(l, mid),      // g
+    ];
+    for (idx, seg) in rects.into_iter().enumerate() {
+        let color = if segments & (1 << idx) != 0 { on } else { off };
+        ui.painter().rect_filled(t * seg, 0.0, color);
+    }
+    let color = if segments & 0x80 != 0 { on } else { off };
+    let dp = egui::pos2(rect.max.x + th, rect.max.y - th * 0.5);
+    ui.painter().circle_filled(t * dp, t * th * 0.6, color);
+}
 
+/// A change to the wire with the input and output in the first field.
+enum WireEdit {
+    MoveAnchor((NodeIdent, NodeIdent), usize, Vec2),
+    SnapAnchor((NodeIdent, NodeIdent), usize),
+    RemoveAnchor((NodeIdent, NodeIdent), usize),
+    InsertAnchor((NodeIdent, NodeIdent), usize, Vec2),
+    MoveEnd((NodeIdent, NodeIdent), WireEnd, Vec2),
+    Remove((NodeIdent, NodeIdent)),
+}
+impl WireEdit {
+    fn wire(&self) -> (NodeIdent, NodeIdent) {
+        match self {
+            Self::MoveAnchor(wire, ..)
+            | Self::SnapAnchor(wire, ..)
+            | Self::RemoveAnchor(wire, ..)
+            | Self::InsertAnchor(wire, ..)
+            | Self::MoveEnd(wire, ..)
+            | Self::Remove(wire) => *wire,
+        }
+    }
+}
+
+struct WireHandles {
+    points: Vec<Vec2>,
+    /// An end of the wire that is being dragged, and the position of the pointer.
+    dragged_end: Option<(Vec2, Vec2)>,
+    active: bool,
+    consumed_click: bool,
+}
+impl WireHandles {
+    fn paint(&self, ui: &Ui, t: Transform, state: u8) {
+        let colors = [Color32::from_rgb(64, 2, 0), Color32::from_rgb(235, 19, 12)];
+        let color = offset_color(colors[(state != 0) as usize], 100);
+        for p in &self.points {
             ui.painter()
-                .circle_filled(t * center, t * UNIT * 0.4, color);
-            place_label(ui, t, bounds, name, LabelPlacement::Right);
+                .circle_filled(t * egui::pos2(p.x, p.y), t * UNIT * 0.2, color);
+        }
+        if let Some((from, to)) = self.dragged_end {
+            let stroke = Stroke::new(t * 2.0, color);
+            ui.painter().line_segment(
+                [t * egui::pos2(from.x, from.y), t * egui::pos2(to.x, to.y)],
+                stroke,
+            );
         }
     }
-    if let Some(id) = rm_device {
-        scene.devices.remove(&id);
+}
+
+/// Interacts with the draggable handles of the wire with the input and output `wire`:
+/// one for each anchor, and one near each end of the wire.
+fn wire_handles(
+    ui: &mut Ui,
+    t: Transform,
+    wire: (NodeIdent, NodeIdent),
+    start: Vec2,
+    end: Vec2,
+    anchors: &[Vec2],
+    edits: &mut Vec<WireEdit>,
+) -> WireHandles {
+    let mut handles = WireHandles {
+        points: vec![],
+        dragged_end: None,
+        active: false,
+        consumed_click: false,
+    };
+    let handle_rect =
+        |p: Vec2| t * Rect::from_center_size(egui::pos2(p.x, p.y), egui::vec2(8.0, 8.0));
+
+    for (idx, anchor) in anchors.iter().enumerate() {
+        let id = Id::new(("wire-anchor", wire, idx));
+        let rs = ui.interact(handle_rect(*anchor), id, Sense::click_and_drag());
+        let delta = vec2(t.inv() * rs.drag_delta().x, t.inv() * rs.drag_delta().y);
+        if rs.dragged() {
+            edits.push(WireEdit::MoveAnchor(wire, idx, delta));
+        }
+        if rs.drag_stopped() {
+            edits.push(WireEdit::SnapAnchor(wire, idx));
+        }
+        if rs.secondary_clicked() {
+            edits.push(WireEdit::RemoveAnchor(wire, idx));
+            handles.consumed_click = true;
+        }
+        handles.active |= rs.hovered() || rs.dragged();
+        handles.points.push(*anchor);
     }
-    rs
+
+    // The handles for the ends sit a little bit away from the pins, so the pins can still be clicked.
+    let first = anchors.first().copied().unwrap_or(end);
+    let last = anchors.last().copied().unwrap_or(start);
+    let ends = [
+        (WireEnd::Input, start, (first - start).normalize_or_zero()),
+        (WireEnd::Output, end, (last - end).normalize_or_zero()),
+    ];
+    for (end, pin, dir) in ends {
+        let pos = pin + dir * UNIT * 0.75;
+        let id = Id::new(("wire-end", wire, end as u8));
+        let rs = ui.interact(handle_rect(pos), id, Sense::drag());
+        if rs.dragged() {
+            let ptr = rs.interact_pointer_pos().unwrap_or_default();
+            handles.dragged_end = Some((pos, t.inv() * vec2(ptr.x, ptr.y)));
+        }
+        if rs.drag_stopped() {
+            let ptr = ui.ctx().pointer_latest_pos().unwrap_or_default();
+            edits.push(WireEdit::MoveEnd(wire, end, t.inv() * vec2(ptr.x, ptr.y)));
+        }
+        handles.active |= rs.hovered() || rs.dragged();
+        handles.points.push(pos);
+    }
+    handles
 }
 
 pub fn draw_external_nodes<P>(
@@ -294,7 +737,7 @@ pub fn draw_external_nodes<P>(
             let w = t * UNIT;
             let colors = [Color32::BLACK, Color32::RED];
 
-            let color = colors[(state == 1) as usize];
+            let color = colors[(state != 0) as usize];
             let rs = ui.interact(
                 Rect::from_center_size(egui::pos2(x, y), egui::vec2(w, w)),
                 Id::from(addr.0.to_string()),
@@ -375,6 +818,14 @@ pub fn draw_external_nodes<P>(
     }
 }
 
+pub struct WireResponse {
+    /// The index of the line segment of the wire the pointer is over.
+    pub hovered_segment: Option<usize>,
+    pub clicked: bool,
+    pub rclicked: bool,
+    pub double_clicked: bool,
+}
+
 pub fn draw_wire(
     ui: &mut Ui,
     t: Transform,
@@ -383,9 +834,7 @@ pub fn draw_wire(
     start: Vec2,
     end: Vec2,
     anchors: &[Vec2],
-) -> (bool, bool) {
-    use crate::ui::line_contains_point;
-
+) -> WireResponse {
     let mut points = std::iter::once(start)
         .chain(anchors.iter().copied())
         .chain(std::iter::once(end));
@@ -401,17 +850,18 @@ pub fn draw_wire(
         prev = n;
     }
 
-    let hovered = !force_unhovered
-        && lines
-            .iter()
-            .any(|line| line_contains_point(*line, 4.0, t.inv() * ptr));
-    let hovered = hovered
-        && ui
-            .ctx()
-            .interaction_snapshot(|ss| ss.contains_pointer.len() <= 2);
+    let hovered_segment = lines
+        .iter()
+        .position(|line| line_contains_point(*line, 4.0, t.inv() * ptr))
+        .filter(|_| !force_unhovered);
+    let hovered_segment = hovered_segment.filter(|_| {
+        ui.ctx()
+            .interaction_snapshot(|ss| ss.contains_pointer.len() <= 2)
+    });
+    let hovered = hovered_segment.is_some();
 
     let colors = [Color32::from_rgb(64, 2, 0), Color32::from_rgb(235, 19, 12)];
-    let mut color = colors[(state == 1) as usize];
+    let mut color = colors[(state != 0) as usize];
     if hovered {
         color = offset_color(color, 60);
     }
@@ -478,5 +928,15 @@ pub fn draw_wire(
             )
         })
     });
-    (hovered && lclicked, hovered && rclicked)
+    let double_clicked = ui.input(|state| {
+        state
+            .pointer
+            .button_double_clicked(egui::PointerButton::Primary)
+    });
+    WireResponse {
+        hovered_segment,
+        clicked: hovered && lclicked,
+        rclicked: hovered && rclicked,
+        double_clicked: hovered && double_clicked,
+    }
 }
@@ -0,0 +1,689 @@
+use crate::sim::bench::{LiveTable, TestBench, MAX_INPUTS, MAX_OUTPUTS};
+use crate::sim::minimize::{format_sop, minimize_output, Implicant};
+use crate::sim::save::Library;
+use crate::sim::scene::Scene;
+use crate::sim::SourceTy;
+
+use egui::{Color32, RichText, Ui};
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// The most inputs a Karnaugh map is shown for.
+const KMAP_MAX_INPUTS: usize = 6;
+
+#[derive(Clone, Copy, PartialEq)]
+enum View {
+    Table,
+    Minimized,
+    KMap,
+}
+
+/// Shows the outputs of a scene or library chip for every combination of it's inputs.
+pub struct TruthTableViewer {
+    /// The library chip that is evaluated, or None for the scene.
+    source: Option<usize>,
+    max_ticks: u32,
+    /// The library table the outputs are compared to.
+    compare: Option<usize>,
+    table: Result<LiveTable, String>,
+    /// A hash of the scene and library the table was evaluated from, to evaluate it again when
+    /// they're edited.
+    evaluated: Option<u64>,
+    view: View,
+    /// The minimized sum of products of each output, computed when first shown.
+    minimized: Option<Vec<Vec<Implicant>>>,
+    /// The output the Karnaugh map is shown for.
+    kmap_output: usize,
+}
+impl Default for TruthTableViewer {
+    fn default() -> Self {
+        Self {
+            source: None,
+            max_ticks: 100,
+            compare: None,
+            table: Err(String::new()),
+            evaluated: None,
+            view: View::Table,
+            minimized: None,
+            kmap_output: 0,
+        }
+    }
+}
+impl TruthTableViewer {
+    pub fn show(&mut self, ui: &mut Ui, scene: &Scene, library: &Library) {
+        let hash = structure_hash(scene, library);
+        if self.evaluated != Some(hash) {
+            self.evaluate(scene, library);
+            self.evaluated = Some(hash);
+        }
+        self.show_toolbar(ui, scene, library);
+        ui.separator();
+        if let (Ok(table), None, View::Minimized | View::KMap) =
+            (&self.table, &self.minimized, self.view)
+        {
+            // Outputs that never settle, or that the compared table doesn't care about, can be
+            // either value
+            let compared = self.compare.map(|idx| &library.tables[idx].dont_care[..]);
+            let dont_care: Vec<u64> = (table.unsettled.iter().enumerate())
+                .map(|(row, unsettled)| {
+                    unsettled | compared.and_then(|dc| dc.get(row)).copied().unwrap_or(0)
+                })
+                .collect();
+            let outputs = (0..table.outputs.len())
+                .map(|output| minimize_output(table.inputs.len(), &table.map, &dont_care, output))
+                .collect();
+            self.minimized = Some(outputs);
+        }
+        match (&self.table, &self.minimized, self.view) {
+            (Err(err), ..) => {
+                ui.colored_label(ui.visuals().error_fg_color, err);
+            }
+            (Ok(table), Some(minimized), View::Minimized) => {
+                show_minimized(ui, table, minimized);
+            }
+            (Ok(table), Some(minimized), View::KMap) => {
+                show_kmap(ui, table, minimized, &mut self.kmap_output);
+            }
+            (Ok(table), ..) => self.show_table(ui, table, library),
+        }
+    }
+
+    fn source_name(scene: &Scene, library: &Library, source: Option<usize>) -> String {
+        match source.and_then(|idx| library.chips.get(idx)) {
+            Some(chip) => chip.attrs.name.clone(),
+            None => format!("{} (scene)", scene.save_attrs.name),
+        }
+    }
+
+    fn evaluate(&mut self, scene: &Scene, library: &Library) {
+        // The chip could have been removed from the library
+        self.source = self.source.filter(|idx| *idx < library.chips.len());
+        let mut bench = match self.source {
+            Some(idx) => TestBench::from_chip(&library.chips[idx]),
+            None => TestBench::from_scene(scene),
+        };
+        self.table = LiveTable::evaluate(&mut bench, &library.tables, self.max_ticks);
+        self.minimized = None;
+        let Ok(table) = &self.table else {
+            return;
+        };
+
+        // Basic chips are compared to their own table by default
+        let own_table = (self.source)
+            .and_then(|idx| library.chips[idx].r_nodes.first())
+            .map(|(_, _, node)| node.source())
+            .filter(|src| src.ty() == SourceTy::TABLE)
+            .map(|src| src.as_table().id().0 as usize);
+        self.compare = (own_table.or(self.compare))
+            .filter(|idx| (library.tables.get(*idx)).is_some_and(|t| table.matches_shape(t)));
+    }
+
+    fn show_toolbar(&mut self, ui: &mut Ui, scene: &Scene, library: &Library) {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("Source: ");
+            egui::ComboBox::from_id_source("source")
+                .selected_text(Self::source_name(scene, library, self.source))
+                .show_ui(ui, |ui| {
+                    let sources = std::iter::once(None).chain((0..library.chips.len()).map(Some));
+                    for source in sources {
+                        let label = Self::source_name(scene, library, source);
+                        changed |= ui
+                            .selectable_value(&mut self.source, source, label)
+                            .changed();
+                    }
+                });
+            let rs = ui.add(
+                egui::DragValue::new(&mut self.max_ticks)
+                    .range(1..=10_000)
+                    .prefix("settle within ")
+                    .suffix(" ticks"),
+            );
+            changed |= rs.changed();
+            if ui.button("Refresh").clicked() {
+                changed = true;
+            }
+            ui.separator();
+
+            ui.selectable_value(&mut self.view, View::Table, "Table");
+            ui.selectable_value(&mut self.view, View::Minimized, "Minimized");
+            ui.selectable_value(&mut self.view, View::KMap, "K-map");
+            ui.separator();
+
+            let Ok(table) = &self.table else {
+                return;
+            };
+            ui.label("Compare with: ");
+            let selected = match self.compare {
+                Some(idx) => library.tables[idx].name.clone(),
+                None => String::from("none"),
+            };
+            let compare = self.compare;
+            egui::ComboBox::from_id_source("compare")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.compare, None, "none");
+                    for (idx, other) in library.tables.iter().enumerate() {
+                        if table.matches_shape(other) {
+                            ui.selectable_value(&mut self.compare, Some(idx), &other.name);
+                        }
+                    }
+                });
+            // The compared table's don't-cares are used when minimizing
+            if self.compare != compare {
+                self.minimized = None;
+            }
+        });
+        if changed {
+            self.evaluate(scene, library);
+        }
+    }
+
+    fn show_table(&self, ui: &mut Ui, table: &LiveTable, library: &Library) {
+        let diff = (self.compare).map(|idx| table.diff(&library.tables[idx]));
+        let unsettled = table.unsettled.iter().filter(|row| **row != 0).count();
+        ui.horizontal(|ui| {
+            ui.label(format!("{} rows", table.map.len()));
+            if unsettled != 0 {
+                let text = format!("{unsettled} rows have outputs that never settle");
+                ui.colored_label(ui.visuals().warn_fg_color, text);
+            }
+            if let Some(diff) = &diff {
+                match diff.iter().filter(|row| **row != 0).count() {
+                    0 => ui.colored_label(Color32::LIGHT_GREEN, "matches the table"),
+                    n => ui.colored_label(ui.visuals().error_fg_color, format!("{n} rows differ")),
+                };
+            }
+        });
+        ui.separator();
+
+        egui::ScrollArea::both().show(ui, |ui| {
+            egui::Grid::new("truth table").striped(true).show(ui, |ui| {
+                for name in &table.inputs {
+                    ui.label(RichText::new(name).strong());
+                }
+                ui.separator();
+                for name in &table.outputs {
+                    ui.label(RichText::new(name).strong());
+                }
+                ui.end_row();
+
+                for (row, outputs) in table.map.iter().enumerate() {
+                    for input in 0..table.inputs.len() {
+                        ui.monospace(format!("{}", (row >> input) & 1));
+                    }
+                    ui.separator();
+                    for output in 0..table.outputs.len() {
+                        let bit = 1 << output;
+                        let value = (outputs & bit != 0) as u8;
+                        if table.unsettled[row] & bit != 0 {
+                            ui.colored_label(ui.visuals().warn_fg_color, "?")
+                                .on_hover_text("never settles");
+                        } else if diff.as_ref().is_some_and(|diff| diff[row] & bit != 0) {
+                            ui.colored_label(ui.visuals().error_fg_color, format!("{value}"))
+                                .on_hover_text(format!("expected {}", value ^ 1));
+                        } else {
+                            ui.monospace(format!("{value}"));
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+    }
+}
+/// A hash of the parts of the scene and library that change what a truth table evaluates to.
+/// The states of the nodes aren't included, as they change every tick.
+fn structure_hash(scene: &Scene, library: &Library) -> u64 {
+    let structure = (&scene.l_nodes, &scene.r_nodes, &scene.devices, &scene.wires);
+    let bytes = bincode::serialize(&(structure, &library.tables, &library.chips));
+    let mut hasher = DefaultHasher::new();
+    bytes.unwrap_or_default().hash(&mut hasher);
+    hasher.finish()
+}
+
+fn show_minimized(ui: &mut Ui, table: &LiveTable, minimized: &[Vec<Implicant>]) {
+    ui.label("Minimized sums of products, outputs that never settle are treated as don't-cares");
+    ui.separator();
+    egui::ScrollArea::both().show(ui, |ui| {
+        egui::Grid::new("minimized").show(ui, |ui| {
+            for (name, implicants) in table.outputs.iter().zip(minimized) {
+                let expr = format!("{name} = {}", format_sop(implicants, &table.inputs));
+                ui.monospace(&expr);
+                ui.label(format!("{} products", implicants.len()));
+                if ui.small_button("copy").clicked() {
+                    ui.output_mut(|out| out.copied_text = expr);
+                }
+                ui.end_row();
+            }
+        });
+    });
+}
+
+/// The label of a row or column of a Karnaugh map, with the first input as the highest bit.
+fn gray_label(gray: usize, count: usize) -> String {
+    (0..count)
+        .map(|idx| match (gray >> (count - 1 - idx)) & 1 {
+            0 => '0',
+            _ => '1',
+        })
+        .collect()
+}
+
+/// Puts the bits of a gray code into the inputs `first..first + count`, in the order they're labeled.
+fn gray_inputs(gray: usize, first: usize, count: usize) -> usize {
+    (0..count)
+        .map(|idx| ((gray >> (count - 1 - idx)) & 1) << (first + idx))
+        .fold(0, |acc, bit| acc | bit)
+}
+
+fn show_kmap(ui: &mut Ui, table: &LiveTable, minimized: &[Vec<Implicant>], output: &mut usize) {
+    let n = table.inputs.len();
+    if n == 0 || n > KMAP_MAX_INPUTS || table.outputs.is_empty() {
+        ui.label(format!(
+            "Karnaugh maps are shown for 1 to {KMAP_MAX_INPUTS} inputs and at least one output"
+        ));
+        return;
+    }
+    *output = (*output).min(table.outputs.len() - 1);
+    ui.horizontal(|ui| {
+        ui.label("Output: ");
+        egui::ComboBox::from_id_source("kmap output")
+            .selected_text(&table.outputs[*output])
+            .show_ui(ui, |ui| {
+                for (idx, name) in table.outputs.iter().enumerate() {
+                    ui.selectable_value(output, idx, name);
+                }
+            });
+    });
+    let implicants = &minimized[*output];
+    let names = &table.inputs;
+    ui.monospace(format!(
+        "{} = {}",
+        table.outputs[*output],
+        format_sop(implicants, names)
+    ));
+    ui.separator();
+
+    // The inputs are split into rows and columns of up to 2 each, and the rest select one of
+    // several maps
+    let row_vars = (n / 2).min(2);
+    let col_vars = (n - row_vars).min(2);
+    let map_vars = n - row_vars - col_vars;
+    let gray = |idx: usize| idx ^ (idx >> 1);
+    let colors = [
+        Color32::from_rgb(90, 40, 40),
+        Color32::from_rgb(40, 90, 40),
+        Color32::from_rgb(40, 40, 110),
+        Color32::from_rgb(90, 90, 30),
+        Color32::from_rgb(90, 30, 90),
+        Color32::from_rgb(30, 90, 90),
+    ];
+    let bit = 1 << *output;
+
+    ui.horizontal_wrapped(|ui| {
+        for map in 0..1 << map_vars {
+            ui.vertical(|ui| {
+                if map_vars != 0 {
+                    let map_names = names[row_vars + col_vars..].join(" ");
+                    ui.label(format!("{map_names} = {}", gray_label(gray(map), map_vars)));
+                }
+                egui::Grid::new(("kmap", map)).show(ui, |ui| {
+                    let row_names = names[..row_vars].join(" ");
+                    let col_names = names[row_vars..row_vars + col_vars].join(" ");
+                    ui.label(RichText::new(format!("{row_names} \\ {col_names}")).strong());
+                    for col in 0..1 << col_vars {
+                        ui.monospace(gray_label(gray(col), col_vars));
+                    }
+                    ui.end_row();
+
+                    for r in 0..1 << row_vars {
+                        ui.monospace(gray_label(gray(r), row_vars));
+                        for col in 0..1 << col_vars {
+                            let row = gray_inputs(gray(r), 0, row_vars)
+                                | gray_inputs(gray(col), row_vars, col_vars)
+                                | gray_inputs(gray(map), row_vars + col_vars, map_vars);
+                            let mut text = match (table.unsettled[row] & bit, table.map[row] & bit)
+                            {
+                                (0, 0) => RichText::new(" 0 "),
+                                (0, _) => RichText::new(" 1 "),
+                                _ => RichText::new(" x "),
+                            }
+                            .monospace();
+                            // Cells are colored by the first product that covers them
+                            let group = (implicants.iter())
+                                .position(|implicant| implicant.covers(row as u64));
+                            if let Some(group) = group {
+                                text = text.background_color(colors[group % colors.len()]);
+                            }
+                            ui.label(text);
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+            ui.add_space(12.0);
+        }
+    });
+}
+
+/// The value of one output in a row of the [TableEditor].
+#[derive(Clone, Copy, PartialEq)]
+enum Cell {
+    Zero,
+    One,
+    DontCare,
+}
+impl Cell {
+    fn parse(text: &str) -> Option<Self> {
+        match text {
+            "0" => Some(Self::Zero),
+            "1" => Some(Self::One),
+            "x" | "X" | "-" | "?" => Some(Self::DontCare),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Zero => "0",
+            Self::One => "1",
+            Self::DontCare => "x",
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            Self::Zero => Self::One,
+            Self::One => Self::DontCare,
+            Self::DontCare => Self::Zero,
+        }
+    }
+}
+
+/// Creates a basic chip from a truth table the user fills in.
+pub struct TableEditor {
+    name: String,
+    category: String,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+    /// The outputs of each combination of inputs, indexed like [crate::sim::TruthTable::map].
+    rows: Vec<Vec<Cell>>,
+    csv: String,
+    status: Result<String, String>,
+}
+impl Default for TableEditor {
+    fn default() -> Self {
+        let mut editor = Self {
+            name: String::from("New Chip"),
+            category: String::from("Basic"),
+            inputs: vec![String::from("a"), String::from("b")],
+            outputs: vec![String::from("out")],
+            rows: vec![],
+            csv: String::new(),
+            status: Ok(String::new()),
+        };
+        editor.resize();
+        editor
+    }
+}
+impl TableEditor {
+    /// Fits the rows to the number of inputs and outputs, keeping the cells that still exist.
+    fn resize(&mut self) {
+        self.rows.resize(1 << self.inputs.len(), vec![]);
+        for row in &mut self.rows {
+            row.resize(self.outputs.len(), Cell::Zero);
+        }
+    }
+
+    /// Reads rows of comma, semicolon or tab separated cells, with the inputs first.
+    /// If the first line is a header of pin names, an empty or `|` column can separate the inputs
+    /// from the outputs, otherwise the current number of inputs is used.
+    /// Rows that aren't in the text are don't-cares.
+    fn import_csv(&mut self, text: &str) -> Result<usize, String> {
+        let mut lines = (text.lines())
+            .map(|line| {
+                (line.split([',', ';', '\t']))
+                    .map(str::trim)
+                    .collect::<Vec<_>>()
+            })
+            .filter(|cells| cells.iter().any(|cell| !cell.is_empty()))
+            .peekable();
+
+        let mut inputs = self.inputs.clone();
+        let mut outputs = self.outputs.clone();
+        let header = (lines.peek())
+            .filter(|cells| {
+                cells
+                    .iter()
+                    .any(|cell| !cell.is_empty() && Cell::parse(cell).is_none())
+            })
+            .cloned();
+        let mut split = None;
+        if let Some(names) = &header {
+            split = names
+                .iter()
+                .position(|name| name.is_empty() || *name == "|");
+            let num_inputs = split.unwrap_or(self.inputs.len());
+            if num_inputs > names.len() {
+                return Err(format!("the header has less than {num_inputs} columns"));
+            }
+            let outputs_start = split.map_or(num_inputs, |split| split + 1);
+            inputs = names[..num_inputs].iter().map(|s| s.to_string()).collect();
+            outputs = names[outputs_start..]
+                .iter()
+                .map(|s| s.to_string())
+                .collect();
+            lines.next();
+        }
+        if inputs.len() > MAX_INPUTS || outputs.len() > MAX_OUTPUTS {
+            return Err(format!(
+                "at most {MAX_INPUTS} inputs and {MAX_OUTPUTS} outputs are supported"
+            ));
+        }
+
+        let mut rows = vec![vec![Cell::DontCare; outputs.len()]; 1 << inputs.len()];
+        let mut count = 0;
+        for (line, cells) in lines.enumerate() {
+            let line = line + 1 + header.is_some() as usize;
+            let mut cells = cells;
+            cells.retain(|cell| *cell != "|");
+            if let Some(split) = split.filter(|split| cells.get(*split) == Some(&"")) {
+                cells.remove(split);
+            }
+            if cells.len() != inputs.len() + outputs.len() {
+                return Err(format!(
+                    "row {line} has {} cells instead of {}",
+                    cells.len(),
+                    inputs.len() + outputs.len()
+                ));
+            }
+            let mut row = 0;
+            for (idx, cell) in cells[..inputs.len()].iter().enumerate() {
+                match *cell {
+                    "0" => {}
+                    "1" => row |= 1 << idx,
+                    _ => return Err(format!("row {line} has the input {cell:?}, not 0 or 1")),
+                }
+            }
+            for (idx, cell) in cells[inputs.len()..].iter().enumerate() {
+                rows[row][idx] = Cell::parse(cell)
+                    .ok_or_else(|| format!("row {line} has the output {cell:?}, not 0, 1 or x"))?;
+            }
+            count += 1;
+        }
+
+        self.inputs = inputs;
+        self.outputs = outputs;
+        self.rows = rows;
+        Ok(count)
+    }
+
+    /// Adds the table to the library, with don't-cares as 0.
+    fn create(&self, library: &mut Library) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err(String::from("the chip needs a name"));
+        }
+        if library
+            .chips
+            .iter()
+            .any(|chip| chip.attrs.name == self.name)
+        {
+            return Err(format!("a chip named {:?} already exists", self.name));
+        }
+        let names: Vec<&str> = self
+            .inputs
+            .iter()
+            .chain(&self.outputs)
+            .map(|s| s.as_str())
+            .collect();
+        for (idx, name) in names.iter().enumerate() {
+            if name.trim().is_empty() {
+                return Err(String::from("every pin needs a name"));
+            }
+            if names[..idx].contains(name) {
+                return Err(format!("more than one pin is named {name:?}"));
+            }
+        }
+        if self.inputs.is_empty() {
+            return Err(String::from("the chip needs at least one input"));
+        }
+        if self.outputs.is_empty() {
+            return Err(String::from("the chip needs at least one output"));
+        }
+
+        let mask = |cell: Cell| -> Box<[u64]> {
+            (self.rows.iter())
+                .map(|row| {
+                    (row.iter().enumerate())
+                        .filter(|(_, other)| **other == cell)
+                        .fold(0u64, |acc, (idx, _)| acc | 1 << idx)
+                })
+                .collect()
+        };
+        // Don't-cares are 0 when simulated, but are kept to minimize the table
+        let (map, dont_care) = (mask(Cell::One), mask(Cell::DontCare));
+        let (inputs, outputs) = names.split_at(self.inputs.len());
+        library.add_basic_chip(&self.name, inputs, outputs, map, dont_care)?;
+        let chip = library.chips.last_mut().unwrap();
+        chip.attrs.category = self.category.clone();
+        Ok(())
+    }
+
+    /// Shows a list of pin names, returning if a pin was added or removed.
+    fn show_pins(
+        ui: &mut Ui,
+        label: &str,
+        prefix: &str,
+        pins: &mut Vec<String>,
+        max: usize,
+    ) -> bool {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label(label);
+            let mut rm_pin = None;
+            for (idx, name) in pins.iter_mut().enumerate() {
+                let rs = ui.add(egui::TextEdit::singleline(name).desired_width(40.0));
+                if rs.secondary_clicked() {
+                    rm_pin = Some(idx);
+                }
+            }
+            if let Some(idx) = rm_pin {
+                pins.remove(idx);
+                changed = true;
+            }
+            if ui
+                .add_enabled(pins.len() < max, egui::Button::new("+"))
+                .clicked()
+            {
+                pins.push(format!("{prefix}{}", pins.len()));
+                changed = true;
+            }
+            if ui
+                .add_enabled(!pins.is_empty(), egui::Button::new("-"))
+                .clicked()
+            {
+                pins.pop();
+                changed = true;
+            }
+        });
+        changed
+    }
+
+    pub fn show(&mut self, ui: &mut Ui, library: &mut Library) {
+        ui.heading("New Truth Table");
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("Name: ");
+            ui.text_edit_singleline(&mut self.name);
+        });
+        ui.horizontal(|ui| {
+            ui.label("Category: ");
+            ui.text_edit_singleline(&mut self.category);
+        });
+        let mut resize = Self::show_pins(ui, "Inputs: ", "in", &mut self.inputs, MAX_INPUTS);
+        resize |= Self::show_pins(ui, "Outputs: ", "out", &mut self.outputs, MAX_OUTPUTS);
+        if resize {
+            self.resize();
+        }
+        ui.small("right click a pin to remove it, click a cell to cycle 0, 1 and don't-care");
+        ui.separator();
+
+        egui::ScrollArea::both().max_height(300.0).show(ui, |ui| {
+            egui::Grid::new("table editor")
+                .striped(true)
+                .show(ui, |ui| {
+                    for name in &self.inputs {
+                        ui.label(RichText::new(name).strong());
+                    }
+                    ui.separator();
+                    for name in &self.outputs {
+                        ui.label(RichText::new(name).strong());
+                    }
+                    ui.end_row();
+
+                    for (idx, row) in self.rows.iter_mut().enumerate() {
+                        for input in 0..self.inputs.len() {
+                            ui.monospace(format!("{}", (idx >> input) & 1));
+                        }
+                        ui.separator();
+                        for cell in row {
+                            let mut button = ui.small_button(cell.label());
+                            if *cell == Cell::DontCare {
+                                button = button.on_hover_text(
+                                    "don't care: simulated as 0, but either value when minimized",
+                                );
+                            }
+                            if button.clicked() {
+                                *cell = cell.next();
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+        ui.separator();
+
+        ui.collapsing("Paste CSV", |ui| {
+            ui.add(
+                egui::TextEdit::multiline(&mut self.csv)
+                    .code_editor()
+                    .desired_rows(4)
+                    .hint_text("a,b,|,out\n0,0,|,0\n1,1,|,1"),
+            );
+            if ui.button("Import").clicked() {
+                let csv = std::mem::take(&mut self.csv);
+                self.status = (self.import_csv(&csv)).map(|count| format!("imported {count} rows"));
+                self.csv = csv;
+            }
+        });
+
+        if ui.button("Add to library").clicked() {
+            self.status = (self.create(library)).map(|_| format!("added {}", self.name));
+        }
+        match &self.status {
+            Ok(msg) => ui.label(msg),
+            Err(err) => ui.colored_label(ui.visuals().error_fg_color, err),
+        };
+    }
+}
@@ -0,0 +1,155 @@
+use crate::sim::bench::{LiveTable, TestBench};
+use crate::sim::save::{ChipSave, Library};
+use crate::sim::vector::{self, TestVector, SETTLE_TICKS};
+
+use egui::{Color32, Ui};
+
+fn pin_names(save: &ChipSave) -> (Vec<String>, Vec<String>) {
+    let names = |nodes: &[(String, _, _)]| nodes.iter().map(|(name, ..)| name.clone()).collect();
+    (names(&save.l_nodes), names(&save.r_nodes))
+}
+
+/// Edits the test vectors of library chips, and runs them.
+pub struct TestEditor {
+    /// The library chip that is edited.
+    chip: Option<usize>,
+    text: String,
+    status: Result<String, String>,
+    /// The result of running the tests of every chip, by chip name.
+    results: Vec<(String, Result<String, String>)>,
+}
+impl Default for TestEditor {
+    fn default() -> Self {
+        Self {
+            chip: None,
+            text: String::new(),
+            status: Ok(String::new()),
+            results: vec![],
+        }
+    }
+}
+impl TestEditor {
+    fn select(&mut self, library: &Library, chip: usize) {
+        let save = &library.chips[chip];
+        let (inputs, outputs) = pin_names(save);
+        self.chip = Some(chip);
+        self.text = vector::format(&save.tests, &inputs, &outputs);
+        self.status = Ok(String::new());
+    }
+
+    /// Parses the text into the tests of the chip.
+    fn save(&mut self, library: &mut Library, chip: usize) -> Result<(), String> {
+        let widths = TestBench::from_chip(&library.chips[chip]).input_widths(&library.tables);
+        let save = &mut library.chips[chip];
+        let (inputs, outputs) = pin_names(save);
+        save.tests = vector::parse(&self.text, &inputs, &outputs, &widths)?;
+        Ok(())
+    }
+
+    /// Replaces the tests with every combination of inputs, expecting the outputs the chip has now.
+    fn record(&mut self, library: &Library, chip: usize) -> Result<(), String> {
+        let save = &library.chips[chip];
+        let mut bench = TestBench::from_chip(save);
+        let table = LiveTable::evaluate(&mut bench, &library.tables, SETTLE_TICKS)?;
+        let vectors: Vec<TestVector> = (table.map.iter().zip(&table.unsettled).enumerate())
+            .map(|(row, (outputs, unsettled))| TestVector {
+                inputs: (0..table.inputs.len())
+                    .map(|idx| Some((row >> idx) as u8 & 1))
+                    .collect(),
+                ticks: 0,
+                outputs: (0..table.outputs.len())
+                    .map(|idx| match unsettled & (1 << idx) {
+                        0 => Some((outputs >> idx) as u8 & 1),
+                        _ => None,
+                    })
+                    .collect(),
+            })
+            .collect();
+        self.text = vector::format(&vectors, &table.inputs, &table.outputs);
+        Ok(())
+    }
+
+    fn run(save: &ChipSave, library: &Library) -> Result<String, String> {
+        match vector::run(save, &library.tables, &save.tests) {
+            Ok(count) => Ok(format!("{count} vectors passed")),
+            Err(failure) => Err(failure.message(&save.tests[failure.row])),
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut Ui, library: &mut Library) {
+        ui.heading("Chip Tests");
+        ui.separator();
+        if self.chip.is_some_and(|chip| chip >= library.chips.len()) {
+            self.chip = None;
+        }
+        let selected = match self.chip {
+            Some(chip) => library.chips[chip].attrs.name.clone(),
+            None => String::from("select a chip"),
+        };
+        let mut select = None;
+        egui::ComboBox::from_id_source("test chip")
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                for (idx, chip) in library.chips.iter().enumerate() {
+                    let label = format!("{} ({} vectors)", chip.attrs.name, chip.tests.len());
+                    if ui.selectable_label(self.chip == Some(idx), label).clicked() {
+                        select = Some(idx);
+                    }
+                }
+            });
+        if let Some(chip) = select {
+            self.select(library, chip);
+        }
+
+        if let Some(chip) = self.chip {
+            ui.add(
+                egui::TextEdit::multiline(&mut self.text)
+                    .code_editor()
+                    .desired_rows(10)
+                    .hint_text("a b | ticks | out\n1 1 | 0 | 1\n0 x | 2 | 0"),
+            );
+            ui.small("inputs | ticks (0 = until settled) | expected outputs, x is a don't-care");
+            ui.horizontal(|ui| {
+                if ui.button("Save").clicked() {
+                    self.status = (self.save(library, chip)).map(|_| String::from("saved"));
+                }
+                if ui.button("Save and run").clicked() {
+                    self.status = (self.save(library, chip))
+                        .and_then(|_| Self::run(&library.chips[chip], library));
+                }
+                let rs = ui.button("Record outputs").on_hover_text(
+                    "fills in every combination of inputs, expecting the outputs the chip has now",
+                );
+                if rs.clicked() {
+                    self.status = (self.record(library, chip)).map(|_| String::from("recorded"));
+                }
+            });
+            match &self.status {
+                Ok(msg) => ui.label(msg),
+                Err(err) => ui.colored_label(ui.visuals().error_fg_color, err),
+            };
+        }
+        ui.separator();
+
+        if ui.button("Run all").clicked() {
+            self.results = (library.chips.iter())
+                .filter(|chip| !chip.tests.is_empty())
+                .map(|chip| (chip.attrs.name.clone(), Self::run(chip, library)))
+                .collect();
+        }
+        egui::ScrollArea::vertical()
+            .max_height(200.0)
+            .show(ui, |ui| {
+                egui::Grid::new("test results").show(ui, |ui| {
+                    for (name, result) in &self.results {
+                        ui.label(name);
+                        match result {
+                            Ok(msg) => ui.colored_label(Color32::LIGHT_GREEN, msg),
+                            Err(err) => ui.colored_label(ui.visuals().error_fg_color, err),
+                        };
+                        ui.end_row();
+                    }
+                });
+            });
+    }
+}
@@ -0,0 +1,272 @@
+use crate::sim::scene::Scene;
+use crate::sim::wave::Recorder;
+use crate::sim::NodeAddr;
+
+use egui::{Align2, Color32, FontId, Pos2, Rect, Sense, Stroke, Ui};
+
+const ROW_H: f32 = 28.0;
+const NAMES_W: f32 = 180.0;
+const RULER_H: f32 = 20.0;
+
+/// Shows the signals recorded in a scene as timing diagrams, as they're recorded.
+pub struct WaveformViewer {
+    /// The nodes of the signals that are shown, from top to bottom.
+    shown: Vec<NodeAddr>,
+    /// The width of one tick, in points.
+    zoom: f32,
+    /// The tick at the left edge of the diagram.
+    offset: f32,
+    /// The ticks marked with the primary and secondary mouse buttons.
+    cursors: [Option<usize>; 2],
+}
+impl WaveformViewer {
+    pub fn new(recorder: &Recorder) -> Self {
+        Self {
+            shown: recorder.signals.iter().map(|signal| signal.addr).collect(),
+            zoom: 8.0,
+            offset: 0.0,
+            cursors: [None; 2],
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut Ui, scene: &mut Scene) {
+        let candidates = scene.signal_candidates();
+        let recorder = &mut scene.sim.recorder;
+        // Signals that stopped being recorded can't be shown
+        self.shown.retain(|addr| recorder.is_recorded(*addr));
+
+        let diagram_w = ui.available_width().max(400.0) - NAMES_W;
+        self.show_toolbar(ui, recorder, diagram_w);
+        ui.horizontal(|ui| {
+            let hidden: Vec<_> = (candidates.into_iter())
+                .filter(|signal| !self.shown.contains(&signal.addr))
+                .collect();
+            ui.add_enabled_ui(!hidden.is_empty(), |ui| {
+                ui.menu_button("add signal", |ui| {
+                    egui::ScrollArea::vertical()
+                        .max_height(300.0)
+                        .show(ui, |ui| {
+                            for signal in hidden {
+                                let label = format!("{}.{}", signal.scope.join("."), signal.name);
+                                if ui.button(label).clicked() {
+                                    self.shown.push(signal.addr);
+                                    recorder.add_signal(signal);
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                });
+            });
+            if !recorder.enabled {
+                ui.checkbox(&mut recorder.enabled, "Record");
+            }
+        });
+        ui.separator();
+        if recorder.is_empty() {
+            ui.label("Nothing was recorded yet, add signals and run the simulation");
+            return;
+        }
+
+        let rows: Vec<usize> = (self.shown.iter())
+            .filter_map(|addr| recorder.signals.iter().position(|s| s.addr == *addr))
+            .collect();
+        let height = RULER_H + rows.len() as f32 * ROW_H;
+        egui::ScrollArea::vertical()
+            .max_height(400.0)
+            .show(ui, |ui| {
+                let width = ui.available_width().max(400.0);
+                let (rect, _) = ui.allocate_exact_size(egui::vec2(width, height), Sense::hover());
+                let (names, diagram) = rect.split_left_right_at_x(rect.min.x + NAMES_W);
+                self.show_names(ui, recorder, &rows, names);
+                self.show_diagram(ui, recorder, &rows, diagram);
+            });
+    }
+
+    fn show_toolbar(&mut self, ui: &mut Ui, recorder: &Recorder, diagram_w: f32) {
+        ui.horizontal(|ui| {
+            if ui.button("+").clicked() {
+                self.zoom = (self.zoom * 2.0).min(200.0);
+            }
+            if ui.button("-").clicked() {
+                self.zoom = (self.zoom * 0.5).max(0.05);
+            }
+            if ui.button("fit").clicked() {
+                self.zoom = diagram_w / recorder.len().max(1) as f32;
+                self.offset = 0.0;
+            }
+            ui.separator();
+
+            let first = recorder.first_tick();
+            for (name, cursor) in ["A", "B"].into_iter().zip(self.cursors) {
+                match cursor {
+                    Some(tick) => ui.label(format!("{name}: {}", first + tick as u64)),
+                    None => ui.label(format!("{name}: -")),
+                };
+            }
+            if let [Some(a), Some(b)] = self.cursors {
+                ui.label(format!("B - A: {} ticks", b as i64 - a as i64));
+            }
+        });
+    }
+
+    /// `rows` are the indices of the shown signals in the recorder.
+    fn show_names(&mut self, ui: &mut Ui, recorder: &Recorder, rows: &[usize], rect: Rect) {
+        let mut ui = ui.child_ui(rect, egui::Layout::top_down(egui::Align::Min), None);
+        ui.add_space(RULER_H);
+        let mut swap = None;
+        let mut remove = None;
+        for (row, signal) in rows.iter().enumerate() {
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(NAMES_W, ROW_H), Sense::hover());
+            let mut row_ui =
+                ui.child_ui(rect, egui::Layout::left_to_right(egui::Align::Center), None);
+            if row_ui.small_button("x").clicked() {
+                remove = Some(row);
+            }
+            if row_ui.small_button("^").clicked() && row > 0 {
+                swap = Some((row, row - 1));
+            }
+            if row_ui.small_button("v").clicked() && row + 1 < rows.len() {
+                swap = Some((row, row + 1));
+            }
+            let mut label = recorder.signals[*signal].name.clone();
+            if let Some(tick) = self.cursors[0].filter(|tick| *tick < recorder.len()) {
+                label += &format!(" = {}", value(recorder, *signal, tick));
+            }
+            row_ui.label(label);
+        }
+        if let Some((a, b)) = swap {
+            self.shown.swap(a, b);
+        }
+        if let Some(row) = remove {
+            self.shown.remove(row);
+        }
+    }
+
+    fn show_diagram(&mut self, ui: &mut Ui, recorder: &Recorder, rows: &[usize], rect: Rect) {
+        let rs = ui.interact(rect, ui.id().with("diagram"), Sense::click_and_drag());
+        let len = recorder.len();
+
+        // Zoom around the pointer, and pan by dragging
+        if let Some(pointer) = rs.hover_pos() {
+            let scroll = ui.input(|input| input.smooth_scroll_delta.y);
+            if scroll != 0.0 {
+                let tick = self.offset + (pointer.x - rect.min.x) / self.zoom;
+                self.zoom = (self.zoom * (scroll * 0.01).exp()).clamp(0.05, 200.0);
+                self.offset = tick - (pointer.x - rect.min.x) / self.zoom;
+            }
+        }
+        self.offset -= rs.drag_delta().x / self.zoom;
+        self.offset = self.offset.clamp(0.0, len.max(1) as f32);
+
+        let tick_at = |x: f32| (self.offset + (x - rect.min.x) / self.zoom) as usize;
+        let x_of = |tick: f32| rect.min.x + (tick - self.offset) * self.zoom;
+        for (button, cursor) in [rs.clicked(), rs.secondary_clicked()]
+            .into_iter()
+            .zip(&mut self.cursors)
+        {
+            if let Some(pos) = rs.interact_pointer_pos().filter(|_| button) {
+                *cursor = Some(tick_at(pos.x).min(len.saturating_sub(1)));
+            }
+        }
+
+        let p = ui.painter_at(rect);
+        p.rect_filled(rect, 0.0, Color32::from_gray(20));
+        let text_color = ui.visuals().text_color();
+        let font = FontId::monospace(11.0);
+
+        // Tick ruler, with a label roughly every 80 points
+        let step = (80.0 / self.zoom).max(1.0) as usize;
+        let step = 10usize.pow((step as f32).log10().ceil() as u32);
+        let first = (self.offset as usize / step) * step;
+        let visible = (rect.width() / self.zoom) as usize + 1;
+        for tick in (first..=(self.offset as usize + visible)).step_by(step) {
+            let x = x_of(tick as f32);
+            p.line_segment(
+                [Pos2::new(x, rect.min.y), Pos2::new(x, rect.max.y)],
+                Stroke::new(1.0, Color32::from_gray(40)),
+            );
+            let label = recorder.first_tick() + tick as u64;
+            p.text(
+                Pos2::new(x + 2.0, rect.min.y),
+                Align2::LEFT_TOP,
+                label,
+                font.clone(),
+                text_color,
+            );
+        }
+
+        // Signals
+        let start = (self.offset as usize).min(len);
+        let end = (start + visible + 1).min(len);
+        for (row, signal) in rows.iter().enumerate() {
+            let top = rect.min.y + RULER_H + row as f32 * ROW_H + 4.0;
+            let bot = top + ROW_H - 8.0;
+            let width = recorder.signals[*signal].width;
+            let stroke = Stroke::new(1.5, Color32::LIGHT_GREEN);
+
+            let mut tick = start;
+            while tick < end {
+                // Draw each run of the same value as one shape
+                let state = recorder.sample(tick, *signal);
+                let mut run_end = tick + 1;
+                while run_end < end && recorder.sample(run_end, *signal) == state {
+                    run_end += 1;
+                }
+                let (x0, x1) = (x_of(tick as f32), x_of(run_end as f32));
+                if width <= 1 {
+                    let y = if state != 0 { top } else { bot };
+                    p.line_segment([Pos2::new(x0, y), Pos2::new(x1, y)], stroke);
+                    if run_end < end {
+                        p.line_segment([Pos2::new(x1, top), Pos2::new(x1, bot)], stroke);
+                    }
+                } else {
+                    let mid = (top + bot) * 0.5;
+                    let slope = (x1 - x0).min(4.0) * 0.5;
+                    let points = vec![
+                        Pos2::new(x0, mid),
+                        Pos2::new(x0 + slope, top),
+                        Pos2::new(x1 - slope, top),
+                        Pos2::new(x1, mid),
+                        Pos2::new(x1 - slope, bot),
+                        Pos2::new(x0 + slope, bot),
+                    ];
+                    p.add(egui::Shape::closed_line(points, stroke));
+                    let text = value(recorder, *signal, tick);
+                    if x1 - x0 > text.len() as f32 * 8.0 + 4.0 {
+                        let center =
+                            Pos2::new((x0.max(rect.min.x) + x1.min(rect.max.x)) * 0.5, mid);
+                        p.text(
+                            center,
+                            Align2::CENTER_CENTER,
+                            text,
+                            font.clone(),
+                            text_color,
+                        );
+                    }
+                }
+                tick = run_end;
+            }
+        }
+
+        // Cursors
+        let colors = [Color32::YELLOW, Color32::LIGHT_BLUE];
+        for (cursor, color) in self.cursors.into_iter().zip(colors) {
+            if let Some(tick) = cursor {
+                let x = x_of(tick as f32 + 0.5);
+                p.line_segment(
+                    [Pos2::new(x, rect.min.y), Pos2::new(x, rect.max.y)],
+                    Stroke::new(1.0, color),
+                );
+            }
+        }
+    }
+}
+
+/// Formats the value of a signal, in hex for buses.
+fn value(recorder: &Recorder, signal: usize, tick: usize) -> String {
+    let state = recorder.sample(tick, signal);
+    match recorder.signals[signal].width {
+        0 | 1 => format!("{state}"),
+        _ => format!("{state:X}"),
+    }
+}
@@ -3,7 +3,7 @@
 use mlsim_common::app::{App, AppInput};
 use mlsim_common::glam::{ivec2, uvec2, vec2, IVec2, UVec2};
 use mlsim_common::{egui, wgpu};
-use mlsim_common::{save::Project, settings::Settings, Platform};
+use mlsim_common::{save, save::Project, settings::Settings, Platform};
 
 use std::path::PathBuf;
 use std::sync::{
@@ -29,28 +29,26 @@ fn save_dir() -> PathBuf {
     dirs.data_dir().to_owned()
 }
 
-fn save_data<T: serde::Serialize>(
-    filename: &str,
-    data: &T,
-) -> Result<PathBuf, (PathBuf, std::io::Error)> {
+fn save_bytes(filename: &str, bytes: &[u8]) -> Result<PathBuf, (PathBuf, std::io::Error)> {
     let dir = save_dir();
     _ = std::fs::create_dir(&dir);
-    let bytes = bincode::serialize(data).unwrap();
     let path = dir.join(filename);
     std::fs::write(&path, bytes)
         .map(|()| path.clone())
         .map_err(|err| (path, err))
 }
 
+fn save_data<T: serde::Serialize>(
+    filename: &str,
+    data: &T,
+) -> Result<PathBuf, (PathBuf, std::io::Error)> {
+    save_bytes(filename, &bincode::serialize(data).unwrap())
+}
+
 fn load_data<T: for<'a> serde::Deserialize<'a>>(filename: &str) -> std::io::Result<T> {
-    let dir = save_dir();
-    let bytes = std::fs::read(dir.join(filename))?;
-    bincode::deserialize(&bytes).map_err(|_| {
-        std::io::Error::new(
-            std::io::ErrorKind::Other,
-            format!("Failed to parse data from file {filename:?}"),
-        )
-    })
+    let bytes = std::fs::read(save_dir().join(filename))?;
+    bincode::deserialize(&bytes)
+        .map_err(|_| std::io::Error::other(format!("Failed to parse data from file {filename:?}")))
 }
 
 static UI_SCALE: AtomicU32 = AtomicU32::new(100);
@@ -76,6 +74,15 @@ impl Platform for DesktopPlatform {
         rs.map(|_| ()).map_err(|(_path, err)| err)
     }
 
+    fn read_file(path: &str) -> std::io::Result<Vec<u8>> {
+        log::info!("Reading {path}...");
+        std::fs::read(path)
+    }
+    fn write_file(path: &str, data: &[u8]) -> std::io::Result<()> {
+        log::info!("Writing {path}...");
+        std::fs::write(path, data)
+    }
+
     #[rustfmt::skip]
     fn can_open_dirs() -> bool { true }
 
@@ -94,8 +101,7 @@ impl Platform for DesktopPlatform {
         return Command::new("xdg-open").arg(&dir).spawn().map(|_| ());
 
         // If none of the above compile flags pass:
-        Err(std::io::Error::new(
-            std::io::ErrorKind::Other,
+        Err(std::io::Error::other(
             "Opending directory not implemented on this operating system",
         ))
     }
@@ -122,10 +128,16 @@ impl Platform for DesktopPlatform {
 
     fn load_project(name: &str) -> std::io::Result<Project> {
         log::info!("Reading {name}.project...");
-        load_data(&format!("{name}.project"))
+        let filename = format!("{name}.project");
+        let bytes = std::fs::read(save_dir().join(&filename))?;
+        save::decode_project(&bytes).map_err(|err| {
+            std::io::Error::other(format!(
+                "Failed to parse data from file {filename:?}: {err}"
+            ))
+        })
     }
     fn save_project(name: &str, project: Project) -> std::io::Result<()> {
-        let rs = save_data(&format!("{name}.project"), &project);
+        let rs = save_bytes(&format!("{name}.project"), &save::encode_project(&project));
         match &rs {
             Ok(path) => log::info!("Saved project {name:?} to {path:?}"),
             Err((path, err)) => log::warn!("Failed to save project {name:?} to {path:?} : {err:?}"),